@@ -0,0 +1,320 @@
+//! Encoding and decoding of `BigInt` values with custom digit symbols.
+
+use crate::parse::{ParseBigIntError, ParseErrorKind};
+use crate::BigInt;
+use std::collections::HashMap;
+
+/// The error returned when a set of symbols cannot be used as an [`Alphabet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphabetError {
+    /// The alphabet has less than 2 or more than 64 symbols.
+    InvalidLength(usize),
+    /// The symbol appears twice, maybe once its case is ignored.
+    DuplicateSymbol(char),
+    /// The symbol is reserved for the sign.
+    ReservedSymbol(char),
+    /// The alias targets a symbol that is not in the alphabet.
+    UnknownSymbol(char),
+}
+
+impl std::fmt::Display for AlphabetError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            AlphabetError::InvalidLength(length) => write!(
+                formatter,
+                "an alphabet needs between 2 and 64 symbols, got {}",
+                length
+            ),
+            AlphabetError::DuplicateSymbol(symbol) => {
+                write!(formatter, "symbol {:?} appears more than once", symbol)
+            }
+            AlphabetError::ReservedSymbol(symbol) => {
+                write!(formatter, "symbol {:?} is reserved for the sign", symbol)
+            }
+            AlphabetError::UnknownSymbol(symbol) => {
+                write!(formatter, "symbol {:?} is not in the alphabet", symbol)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AlphabetError {}
+
+/// The digit symbols used to write a `BigInt` in a radix from 2 to 64.
+///
+/// The first symbol stands for 0, the second for 1, and so on. Negative values are written with
+/// a leading `-`, so `-` cannot be a symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    /// Symbol of each digit value.
+    symbols: Vec<char>,
+
+    /// Digit value of each accepted symbol, aliases included.
+    values: HashMap<char, u8>,
+
+    /// Accept the symbols in any case when decoding.
+    case_insensitive: bool,
+}
+
+impl Alphabet {
+    /// Create a new alphabet from its symbols, in digit order.
+    pub fn new(symbols: &str) -> Result<Self, AlphabetError> {
+        let symbols: Vec<char> = symbols.chars().collect();
+
+        if !(2..=64).contains(&symbols.len()) {
+            return Err(AlphabetError::InvalidLength(symbols.len()));
+        }
+
+        let mut alphabet = Alphabet {
+            symbols: symbols.clone(),
+            values: HashMap::new(),
+            case_insensitive: false,
+        };
+
+        for (value, &symbol) in symbols.iter().enumerate() {
+            alphabet.insert(symbol, value as u8)?;
+        }
+
+        Ok(alphabet)
+    }
+
+    /// Accept the symbols in either case when decoding. Encoding still uses the symbols as
+    /// given.
+    pub fn case_insensitive(self) -> Result<Self, AlphabetError> {
+        let mut alphabet = Alphabet {
+            symbols: self.symbols,
+            values: HashMap::new(),
+            case_insensitive: true,
+        };
+
+        // Sorted so that a clash is always reported on the same symbol.
+        let mut values: Vec<(u8, char)> = self
+            .values
+            .into_iter()
+            .map(|(symbol, value)| (value, symbol))
+            .collect();
+        values.sort_unstable();
+
+        for (value, symbol) in values {
+            alphabet.insert(symbol, value)?;
+        }
+
+        Ok(alphabet)
+    }
+
+    /// Accept `alias` as another spelling of `symbol` when decoding.
+    pub fn with_alias(mut self, alias: char, symbol: char) -> Result<Self, AlphabetError> {
+        let value = self
+            .value(symbol)
+            .ok_or(AlphabetError::UnknownSymbol(symbol))?;
+
+        self.insert(alias, value)?;
+
+        Ok(self)
+    }
+
+    /// The 62 alphanumeric symbols `0-9`, `A-Z` then `a-z`.
+    pub fn base62() -> Self {
+        Alphabet::new("0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz")
+            .expect("the base62 alphabet is valid")
+    }
+
+    /// Douglas Crockford's base32: no `I`, `L`, `O` or `U`, decoded in either case, with `I`
+    /// and `L` read as `1` and `O` read as `0`.
+    pub fn crockford_base32() -> Self {
+        Alphabet::new("0123456789ABCDEFGHJKMNPQRSTVWXYZ")
+            .and_then(Alphabet::case_insensitive)
+            .and_then(|alphabet| alphabet.with_alias('I', '1'))
+            .and_then(|alphabet| alphabet.with_alias('L', '1'))
+            .and_then(|alphabet| alphabet.with_alias('O', '0'))
+            .expect("the Crockford base32 alphabet is valid")
+    }
+
+    /// Returns the radix of the alphabet, that is its number of symbols.
+    pub fn radix(&self) -> u8 {
+        self.symbols.len() as u8
+    }
+
+    /// Returns the digit value of a symbol.
+    fn value(&self, symbol: char) -> Option<u8> {
+        self.values.get(&symbol).copied()
+    }
+
+    /// Register a symbol, in both cases if the alphabet is case-insensitive.
+    fn insert(&mut self, symbol: char, value: u8) -> Result<(), AlphabetError> {
+        if symbol == '-' {
+            return Err(AlphabetError::ReservedSymbol(symbol));
+        }
+
+        let spellings: Vec<char> = if self.case_insensitive {
+            symbol.to_lowercase().chain(symbol.to_uppercase()).collect()
+        } else {
+            vec![symbol]
+        };
+
+        for spelling in spellings {
+            if let Some(previous) = self.values.insert(spelling, value) {
+                if previous != value {
+                    return Err(AlphabetError::DuplicateSymbol(spelling));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl BigInt {
+    /// Returns the number encoded as a string with the symbols of the alphabet.
+    pub fn to_string_with_alphabet(&self, alphabet: &Alphabet) -> String {
+        let sign = if self.is_negative() { "-" } else { "" };
+
+        sign.chars()
+            .chain(
+                self.to_digits(alphabet.radix())
+                    .into_iter()
+                    .map(|digit| alphabet.symbols[digit as usize]),
+            )
+            .collect()
+    }
+
+    /// Create a new `BigInt` from a string written with the symbols of the alphabet, preceded
+    /// by an optional `-`.
+    pub fn from_string_with_alphabet(
+        data: &str,
+        alphabet: &Alphabet,
+    ) -> Result<Self, ParseBigIntError> {
+        let (negative, start) = match data.strip_prefix('-') {
+            Some(_) => (true, 1),
+            None => (false, 0),
+        };
+
+        if data.len() == start {
+            return Err(ParseBigIntError::new(ParseErrorKind::Empty, start));
+        }
+
+        let mut digits = Vec::with_capacity(data.len() - start);
+
+        for (offset, character) in data[start..].char_indices() {
+            match alphabet.value(character) {
+                Some(value) => digits.push(value),
+                None => {
+                    let kind = match character {
+                        '-' => ParseErrorKind::MisplacedSign,
+                        _ => ParseErrorKind::InvalidDigit,
+                    };
+
+                    return Err(ParseBigIntError::new(kind, start + offset));
+                }
+            }
+        }
+
+        Ok(BigInt::from_digits(&digits, alphabet.radix(), negative))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Alphabet, AlphabetError, BigInt, ParseErrorKind};
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn should_reject_invalid_alphabets() {
+            // When / Then
+            assert_eq!(Alphabet::new("0"), Err(AlphabetError::InvalidLength(1)));
+            assert_eq!(
+                Alphabet::new("0120"),
+                Err(AlphabetError::DuplicateSymbol('0'))
+            );
+            assert_eq!(
+                Alphabet::new("01-"),
+                Err(AlphabetError::ReservedSymbol('-'))
+            );
+            assert_eq!(
+                Alphabet::base62().case_insensitive(),
+                Err(AlphabetError::DuplicateSymbol('a'))
+            );
+        }
+
+        #[test]
+        fn should_encode_with_custom_symbols() {
+            // Given
+            let alphabet = Alphabet::new("ab").unwrap();
+
+            // When
+            let result = BigInt::from(-6i8).to_string_with_alphabet(&alphabet);
+
+            // Then
+            assert_eq!(result, "-bba");
+            assert_eq!(
+                BigInt::from_string_with_alphabet("-bba", &alphabet),
+                Ok(BigInt::from(-6i8))
+            );
+        }
+    }
+
+    mod base62 {
+        use super::*;
+
+        #[test]
+        fn should_round_trip_base62() {
+            // Given
+            let alphabet = Alphabet::base62();
+            let value = BigInt::from(u128::MAX);
+
+            // When
+            let encoded = value.to_string_with_alphabet(&alphabet);
+
+            // Then
+            assert_eq!(encoded, "7n42DGM5Tflk9n8mt7Fhc7");
+            assert_eq!(
+                BigInt::from_string_with_alphabet(&encoded, &alphabet),
+                Ok(value)
+            );
+        }
+
+        #[test]
+        fn should_encode_zero_with_the_first_symbol() {
+            // When
+            let result = BigInt::from(0u8).to_string_with_alphabet(&Alphabet::base62());
+
+            // Then
+            assert_eq!(result, "0");
+        }
+    }
+
+    mod crockford_base32 {
+        use super::*;
+
+        #[test]
+        fn should_decode_in_any_case_with_aliases() {
+            // Given
+            let alphabet = Alphabet::crockford_base32();
+
+            // When
+            let canonical = BigInt::from_string_with_alphabet("1ZZ0", &alphabet);
+            let aliased = BigInt::from_string_with_alphabet("lzzo", &alphabet);
+
+            // Then
+            assert_eq!(canonical, Ok(BigInt::from(65504u16)));
+            assert_eq!(aliased, canonical);
+            assert_eq!(
+                BigInt::from(65504u16).to_string_with_alphabet(&alphabet),
+                "1ZZ0"
+            );
+        }
+
+        #[test]
+        fn should_reject_excluded_symbols() {
+            // When
+            let error =
+                BigInt::from_string_with_alphabet("1U", &Alphabet::crockford_base32()).unwrap_err();
+
+            // Then
+            assert_eq!(error.kind(), ParseErrorKind::InvalidDigit);
+            assert_eq!(error.position(), 1);
+        }
+    }
+}
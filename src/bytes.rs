@@ -0,0 +1,270 @@
+//! Import and export of `BigInt` values as big- and little-endian bytes, for the magnitude alone
+//! or in two's complement.
+
+use crate::magnitude;
+use crate::{BigInt, Sign};
+
+/// The error returned when a `BigInt` does not fit in the requested number of bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteLengthError {
+    /// Number of bytes that were requested.
+    length: usize,
+
+    /// Smallest number of bytes the value fits in.
+    required: usize,
+}
+
+impl ByteLengthError {
+    /// Returns the number of bytes that were requested.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns the smallest number of bytes the value fits in.
+    pub fn required(&self) -> usize {
+        self.required
+    }
+}
+
+impl std::fmt::Display for ByteLengthError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            formatter,
+            "BigInt needs {} bytes, more than the {} requested",
+            self.required, self.length
+        )
+    }
+}
+
+impl std::error::Error for ByteLengthError {}
+
+impl BigInt {
+    /// Create a new `BigInt` from a sign and a big-endian magnitude.
+    /// `Sign::NoSign` always gives zero, whatever the magnitude.
+    pub fn from_bytes_be(sign: Sign, data: &[u8]) -> Self {
+        BigInt::new(sign, data.iter().rev().copied().collect())
+    }
+
+    /// Create a new `BigInt` from little-endian bytes in two's complement.
+    /// An empty slice is zero.
+    pub fn from_signed_bytes_le(data: &[u8]) -> Self {
+        BigInt::from_twos_complement(data.to_vec())
+    }
+
+    /// Create a new `BigInt` from big-endian bytes in two's complement.
+    /// An empty slice is zero.
+    pub fn from_signed_bytes_be(data: &[u8]) -> Self {
+        BigInt::from_twos_complement(data.iter().rev().copied().collect())
+    }
+
+    /// Returns the magnitude as little-endian bytes, without high zero bytes. Zero is a single
+    /// `0` byte. The sign is dropped.
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let data = self.magnitude();
+
+        if data.is_empty() {
+            vec![0]
+        } else {
+            data.to_vec()
+        }
+    }
+
+    /// Returns the magnitude as big-endian bytes, without high zero bytes. Zero is a single `0`
+    /// byte. The sign is dropped.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+
+        bytes
+    }
+
+    /// Returns the value in little-endian two's complement, in the fewest bytes that keep the
+    /// sign bit.
+    pub fn to_signed_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = self.to_twos_complement(self.magnitude().len() + 1);
+
+        // Drop the bytes that only extend the sign of the next one.
+        while let [.., next, last] = bytes[..] {
+            let redundant = match last {
+                0x00 => next & 0x80 == 0,
+                0xFF => next & 0x80 != 0,
+                _ => false,
+            };
+
+            if !redundant {
+                break;
+            }
+
+            bytes.pop();
+        }
+
+        bytes
+    }
+
+    /// Returns the value in big-endian two's complement, in the fewest bytes that keep the sign
+    /// bit.
+    pub fn to_signed_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_signed_bytes_le();
+        bytes.reverse();
+
+        bytes
+    }
+
+    /// Returns the magnitude as little-endian bytes, zero-padded to `length` bytes.
+    pub fn to_bytes_le_padded(&self, length: usize) -> Result<Vec<u8>, ByteLengthError> {
+        let mut bytes = magnitude::significant(&self.data).to_vec();
+
+        check_length(bytes.len(), length)?;
+        bytes.resize(length, 0);
+
+        Ok(bytes)
+    }
+
+    /// Returns the magnitude as big-endian bytes, zero-padded to `length` bytes.
+    pub fn to_bytes_be_padded(&self, length: usize) -> Result<Vec<u8>, ByteLengthError> {
+        let mut bytes = self.to_bytes_le_padded(length)?;
+        bytes.reverse();
+
+        Ok(bytes)
+    }
+
+    /// Returns the value in little-endian two's complement, sign-extended to `length` bytes.
+    pub fn to_signed_bytes_le_padded(&self, length: usize) -> Result<Vec<u8>, ByteLengthError> {
+        let mut bytes = self.to_signed_bytes_le();
+        let extension = if self.is_negative() { 0xFF } else { 0x00 };
+
+        check_length(bytes.len(), length)?;
+        bytes.resize(length, extension);
+
+        Ok(bytes)
+    }
+
+    /// Returns the value in big-endian two's complement, sign-extended to `length` bytes.
+    pub fn to_signed_bytes_be_padded(&self, length: usize) -> Result<Vec<u8>, ByteLengthError> {
+        let mut bytes = self.to_signed_bytes_le_padded(length)?;
+        bytes.reverse();
+
+        Ok(bytes)
+    }
+}
+
+/// Check that `required` bytes fit in `length`.
+fn check_length(required: usize, length: usize) -> Result<(), ByteLengthError> {
+    if required > length {
+        return Err(ByteLengthError { length, required });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigInt, ByteLengthError, Sign};
+
+    mod magnitude {
+        use super::*;
+
+        #[test]
+        fn should_export_in_both_orders() {
+            // Given
+            let value = BigInt::from(-0x0001_0203_i32);
+
+            // When / Then
+            assert_eq!(value.to_bytes_le(), vec![0x03, 0x02, 0x01]);
+            assert_eq!(value.to_bytes_be(), vec![0x01, 0x02, 0x03]);
+            assert_eq!(BigInt::from(0u8).to_bytes_be(), vec![0]);
+        }
+
+        #[test]
+        fn should_import_big_endian_bytes() {
+            // When
+            let value = BigInt::from_bytes_be(Sign::Minus, &[0x00, 0x01, 0x02, 0x03]);
+
+            // Then
+            assert_eq!(value, BigInt::from(-0x0001_0203_i32));
+            assert_eq!(BigInt::from_bytes_be(Sign::Plus, &[]), BigInt::from(0u8));
+        }
+
+        #[test]
+        fn should_pad_to_a_fixed_length() {
+            // Given
+            let value = BigInt::from(0xABCDu16);
+
+            // When / Then
+            assert_eq!(value.to_bytes_be_padded(4), Ok(vec![0, 0, 0xAB, 0xCD]));
+            assert_eq!(value.to_bytes_le_padded(2), Ok(vec![0xCD, 0xAB]));
+            assert_eq!(BigInt::from(0u8).to_bytes_be_padded(0), Ok(vec![]));
+        }
+
+        #[test]
+        fn should_reject_values_longer_than_the_padding() {
+            // When
+            let error = BigInt::from(0x0001_0000_u32)
+                .to_bytes_be_padded(2)
+                .unwrap_err();
+
+            // Then
+            assert_eq!(error.length(), 2);
+            assert_eq!(error.required(), 3);
+            assert_eq!(
+                error.to_string(),
+                "BigInt needs 3 bytes, more than the 2 requested"
+            );
+        }
+    }
+
+    mod twos_complement {
+        use super::*;
+
+        #[test]
+        fn should_export_the_minimal_form() {
+            // When / Then
+            assert_eq!(BigInt::from(0u8).to_signed_bytes_be(), vec![0x00]);
+            assert_eq!(BigInt::from(127u8).to_signed_bytes_be(), vec![0x7F]);
+            assert_eq!(BigInt::from(128u8).to_signed_bytes_be(), vec![0x00, 0x80]);
+            assert_eq!(BigInt::from(-1i8).to_signed_bytes_be(), vec![0xFF]);
+            assert_eq!(BigInt::from(-128i16).to_signed_bytes_be(), vec![0x80]);
+            assert_eq!(BigInt::from(-129i16).to_signed_bytes_be(), vec![0xFF, 0x7F]);
+            assert_eq!(
+                BigInt::from(-65536i32).to_signed_bytes_le(),
+                vec![0x00, 0x00, 0xFF]
+            );
+        }
+
+        #[test]
+        fn should_round_trip() {
+            for value in [0i64, 1, -1, 255, -256, i64::MAX, i64::MIN] {
+                // Given
+                let value = BigInt::from(value);
+
+                // When
+                let big_endian = value.to_signed_bytes_be();
+                let little_endian = value.to_signed_bytes_le();
+
+                // Then
+                assert_eq!(BigInt::from_signed_bytes_be(&big_endian), value);
+                assert_eq!(BigInt::from_signed_bytes_le(&little_endian), value);
+            }
+        }
+
+        #[test]
+        fn should_sign_extend_to_a_fixed_length() {
+            // When / Then
+            assert_eq!(
+                BigInt::from(-2i8).to_signed_bytes_be_padded(4),
+                Ok(vec![0xFF, 0xFF, 0xFF, 0xFE])
+            );
+            assert_eq!(
+                BigInt::from(2u8).to_signed_bytes_le_padded(3),
+                Ok(vec![0x02, 0x00, 0x00])
+            );
+            assert_eq!(
+                BigInt::from(128u8).to_signed_bytes_be_padded(1),
+                Err(ByteLengthError {
+                    length: 1,
+                    required: 2
+                })
+            );
+        }
+    }
+}
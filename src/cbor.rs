@@ -0,0 +1,323 @@
+//! CBOR (RFC 8949) integers of any size: major types 0 and 1 up to 64 bits, and the bignums
+//! of tags 2 and 3 beyond.
+
+use crate::decode::{self, DecodeError, DecodeErrorKind};
+use crate::{BigInt, Sign};
+
+/// Major type of the unsigned integers.
+const UNSIGNED: u8 = 0;
+
+/// Major type of the negative integers, `-1 - argument`.
+const NEGATIVE: u8 = 1;
+
+/// Major type of the byte strings.
+const BYTE_STRING: u8 = 2;
+
+/// Major type of the tags.
+const TAG: u8 = 6;
+
+/// Tag of an unsigned bignum.
+const POSITIVE_BIGNUM: u64 = 2;
+
+/// Tag of a negative bignum, `-1 - magnitude`.
+const NEGATIVE_BIGNUM: u64 = 3;
+
+/// Additional information of an indefinite length.
+const INDEFINITE: u8 = 31;
+
+/// The "break" stop code that ends an indefinite-length item.
+const BREAK: u8 = 0xFF;
+
+/// The initial byte and argument of a data item.
+struct Head {
+    /// Major type, the top 3 bits of the initial byte.
+    major: u8,
+
+    /// Argument, or `None` for an indefinite length.
+    argument: Option<u64>,
+
+    /// Position of the first byte after the head.
+    end: usize,
+}
+
+impl BigInt {
+    /// Returns the value as a CBOR integer: major type 0 or 1 when it fits in 64 bits, a tag 2
+    /// or 3 bignum otherwise. The encoding is the preferred (shortest) one.
+    pub fn to_cbor(&self) -> Vec<u8> {
+        // Negative values encode `-1 - n`, that is `|n| - 1`.
+        let (major, tag, value) = if self.is_negative() {
+            (NEGATIVE, NEGATIVE_BIGNUM, self.abs() - 1u8)
+        } else {
+            (UNSIGNED, POSITIVE_BIGNUM, self.clone())
+        };
+
+        let mut result = Vec::new();
+
+        match u64::try_from(&value) {
+            Ok(argument) => write_head(&mut result, major, argument),
+            Err(_) => {
+                let bytes = value.to_bytes_be();
+
+                write_head(&mut result, TAG, tag);
+                write_head(&mut result, BYTE_STRING, bytes.len() as u64);
+                result.extend_from_slice(&bytes);
+            }
+        }
+
+        result
+    }
+
+    /// Decode a CBOR integer or bignum from the start of `data`, accepting any valid encoding.
+    /// Returns the value and the number of bytes read.
+    pub fn from_cbor(data: &[u8]) -> Result<(Self, usize), DecodeError> {
+        decode_integer(data, false)
+    }
+
+    /// Decode a CBOR integer or bignum from the start of `data`, rejecting the encodings that
+    /// are not the preferred one: longer arguments, indefinite lengths, leading zero bytes, and
+    /// bignums of values that fit in 64 bits. Returns the value and the number of bytes read.
+    pub fn from_cbor_strict(data: &[u8]) -> Result<(Self, usize), DecodeError> {
+        decode_integer(data, true)
+    }
+}
+
+/// Append the initial byte and the shortest argument of a data item.
+fn write_head(result: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+
+    match argument {
+        0..24 => result.push(major | argument as u8),
+        24..0x100 => result.extend_from_slice(&[major | 24, argument as u8]),
+        0x100..0x1_0000 => {
+            result.push(major | 25);
+            result.extend_from_slice(&(argument as u16).to_be_bytes());
+        }
+        0x1_0000..0x1_0000_0000 => {
+            result.push(major | 26);
+            result.extend_from_slice(&(argument as u32).to_be_bytes());
+        }
+        _ => {
+            result.push(major | 27);
+            result.extend_from_slice(&argument.to_be_bytes());
+        }
+    }
+}
+
+/// Read the head of the data item at `position`, checking that its argument is the shortest
+/// one if `strict`.
+fn read_head(data: &[u8], position: usize, strict: bool) -> Result<Head, DecodeError> {
+    let initial = decode::take(data, position, 1)?[0];
+    let (major, information) = (initial >> 5, initial & 0x1F);
+
+    let (argument, end) = match information {
+        0..24 => (Some(information as u64), position + 1),
+        24..28 => {
+            let length = 1 << (information - 24);
+            let bytes = decode::take(data, position + 1, length)?;
+            let argument = bytes
+                .iter()
+                .fold(0u64, |argument, &byte| argument << 8 | byte as u64);
+
+            // The argument would fit in the initial byte or in a shorter field.
+            let minimum = if length == 1 { 24 } else { 1 << (length * 4) };
+
+            if strict && argument < minimum {
+                return Err(DecodeError::new(DecodeErrorKind::NonMinimal, position));
+            }
+
+            (Some(argument), position + 1 + length)
+        }
+        INDEFINITE if strict => {
+            return Err(DecodeError::new(DecodeErrorKind::NonMinimal, position));
+        }
+        INDEFINITE => (None, position + 1),
+        _ => return Err(DecodeError::new(DecodeErrorKind::InvalidLength, position)),
+    };
+
+    Ok(Head {
+        major,
+        argument,
+        end,
+    })
+}
+
+/// Decode an integer or a bignum, checking that it is the preferred encoding if `strict`.
+fn decode_integer(data: &[u8], strict: bool) -> Result<(BigInt, usize), DecodeError> {
+    let head = read_head(data, 0, strict)?;
+
+    let Some(argument) = head.argument else {
+        return Err(DecodeError::new(DecodeErrorKind::InvalidLength, 0));
+    };
+
+    match head.major {
+        UNSIGNED => Ok((BigInt::from(argument), head.end)),
+        NEGATIVE => Ok((-(BigInt::from(argument) + 1u8), head.end)),
+        TAG if argument == POSITIVE_BIGNUM || argument == NEGATIVE_BIGNUM => {
+            let (bytes, end) = read_byte_string(data, head.end, strict)?;
+
+            if strict && (bytes.first() == Some(&0) || bytes.len() <= 8) {
+                return Err(DecodeError::new(DecodeErrorKind::NonMinimal, head.end));
+            }
+
+            let value = BigInt::from_bytes_be(Sign::Plus, &bytes);
+
+            if argument == NEGATIVE_BIGNUM {
+                Ok((-(value + 1u8), end))
+            } else {
+                Ok((value, end))
+            }
+        }
+        _ => Err(DecodeError::new(DecodeErrorKind::UnexpectedTag, 0)),
+    }
+}
+
+/// Returns the contents of the byte string at `position` and the position after it. An
+/// indefinite-length string is the concatenation of its chunks.
+fn read_byte_string(
+    data: &[u8],
+    position: usize,
+    strict: bool,
+) -> Result<(Vec<u8>, usize), DecodeError> {
+    let head = read_head(data, position, strict)?;
+
+    if head.major != BYTE_STRING {
+        return Err(DecodeError::new(DecodeErrorKind::UnexpectedTag, position));
+    }
+
+    let Some(length) = head.argument else {
+        let mut bytes = Vec::new();
+        let mut position = head.end;
+
+        while decode::take(data, position, 1)?[0] != BREAK {
+            // Chunks are definite-length strings themselves.
+            if data[position] & 0x1F == INDEFINITE {
+                return Err(DecodeError::new(DecodeErrorKind::InvalidLength, position));
+            }
+
+            let (chunk, end) = read_byte_string(data, position, strict)?;
+            bytes.extend_from_slice(&chunk);
+            position = end;
+        }
+
+        return Ok((bytes, position + 1));
+    };
+
+    let length = usize::try_from(length)
+        .map_err(|_| DecodeError::new(DecodeErrorKind::InvalidLength, position))?;
+
+    Ok((
+        decode::take(data, head.end, length)?.to_vec(),
+        head.end + length,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigInt, DecodeErrorKind};
+
+    /// 2^64, the smallest value that needs a bignum.
+    fn two_to_the_64() -> BigInt {
+        BigInt::from(1u8) << 64
+    }
+
+    mod encode {
+        use super::*;
+
+        #[test]
+        fn should_use_the_integer_major_types_up_to_64_bits() {
+            // When / Then
+            assert_eq!(BigInt::from(0u8).to_cbor(), vec![0x00]);
+            assert_eq!(BigInt::from(23u8).to_cbor(), vec![0x17]);
+            assert_eq!(BigInt::from(24u8).to_cbor(), vec![0x18, 0x18]);
+            assert_eq!(BigInt::from(1000u16).to_cbor(), vec![0x19, 0x03, 0xE8]);
+            assert_eq!(BigInt::from(-1i8).to_cbor(), vec![0x20]);
+            assert_eq!(BigInt::from(-1000i16).to_cbor(), vec![0x39, 0x03, 0xE7]);
+            assert_eq!(
+                BigInt::from(u64::MAX).to_cbor(),
+                vec![0x1B, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+            );
+            assert_eq!(BigInt::from(i64::MIN).to_cbor()[0], 0x3B);
+        }
+
+        #[test]
+        fn should_use_bignums_beyond_64_bits() {
+            // Given: the examples of RFC 8949, appendix A.
+            let positive = two_to_the_64();
+            let negative = -two_to_the_64() - 1u8;
+
+            // When / Then
+            assert_eq!(
+                positive.to_cbor(),
+                vec![0xC2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            );
+            assert_eq!(
+                negative.to_cbor(),
+                vec![0xC3, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            );
+        }
+    }
+
+    mod decode {
+        use super::*;
+
+        #[test]
+        fn should_round_trip() {
+            for value in [
+                BigInt::from(0u8),
+                BigInt::from_bytes(vec![], true),
+                BigInt::from(-1i8),
+                BigInt::from(u64::MAX),
+                -BigInt::from(u64::MAX) - 1u8,
+                two_to_the_64(),
+                -two_to_the_64() - 1u8,
+                BigInt::from(-7i8) << 1000,
+            ] {
+                // When
+                let encoded = value.to_cbor();
+
+                // Then
+                assert_eq!(
+                    BigInt::from_cbor_strict(&encoded),
+                    Ok((value, encoded.len()))
+                );
+            }
+        }
+
+        #[test]
+        fn should_accept_non_preferred_encodings_unless_strict() {
+            for (data, expected) in [
+                (&[0x18, 0x05][..], BigInt::from(5u8)),
+                (&[0x3A, 0x00, 0x00, 0x01, 0x00], BigInt::from(-257i16)),
+                (&[0xC2, 0x42, 0x00, 0x2A], BigInt::from(42u8)),
+                (&[0xC3, 0x40], BigInt::from(-1i8)),
+                (
+                    &[0xC2, 0x5F, 0x41, 0x01, 0x41, 0x02, 0xFF],
+                    BigInt::from(258u16),
+                ),
+            ] {
+                // When
+                let strict = BigInt::from_cbor_strict(data).unwrap_err();
+                let lenient = BigInt::from_cbor(data);
+
+                // Then
+                assert_eq!(strict.kind(), DecodeErrorKind::NonMinimal);
+                assert_eq!(lenient, Ok((expected, data.len())));
+            }
+        }
+
+        #[test]
+        fn should_reject_other_items() {
+            // When / Then
+            let error = |data: &[u8]| BigInt::from_cbor(data).unwrap_err().kind();
+
+            assert_eq!(error(&[0x41, 0x01]), DecodeErrorKind::UnexpectedTag);
+            assert_eq!(error(&[0xC4, 0x41, 0x01]), DecodeErrorKind::UnexpectedTag);
+            assert_eq!(error(&[0xC2, 0x01]), DecodeErrorKind::UnexpectedTag);
+            assert_eq!(error(&[0x1C]), DecodeErrorKind::InvalidLength);
+            assert_eq!(error(&[0x1F]), DecodeErrorKind::InvalidLength);
+            assert_eq!(error(&[0x19, 0x01]), DecodeErrorKind::Truncated);
+            assert_eq!(error(&[0xC2, 0x45, 0x01]), DecodeErrorKind::Truncated);
+            assert_eq!(error(&[0xC2, 0x5F, 0x41, 0x01]), DecodeErrorKind::Truncated);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Conversions between `BigInt` and the primitive integers.
+
+use crate::magnitude;
+use crate::BigInt;
+
+/// The error returned when a `BigInt` does not fit in the requested primitive type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TryFromBigIntError {
+    /// Name of the primitive type that was requested.
+    target: &'static str,
+}
+
+impl TryFromBigIntError {
+    /// Returns the name of the primitive type the value did not fit in.
+    pub fn target(&self) -> &'static str {
+        self.target
+    }
+}
+
+impl std::fmt::Display for TryFromBigIntError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(formatter, "BigInt out of range for type {}", self.target)
+    }
+}
+
+impl std::error::Error for TryFromBigIntError {}
+
+impl BigInt {
+    /// Returns the magnitude if it fits in 128 bits.
+    fn magnitude_u128(&self) -> Option<u128> {
+        let data = magnitude::significant(&self.data);
+
+        if data.len() > 16 {
+            return None;
+        }
+
+        let mut bytes = [0u8; 16];
+        bytes[..data.len()].copy_from_slice(data);
+
+        Some(u128::from_le_bytes(bytes))
+    }
+
+    /// Returns the lowest 128 bits of the value in two's complement.
+    fn low_u128(&self) -> u128 {
+        let data = &self.data[..self.data.len().min(16)];
+
+        let mut bytes = [0u8; 16];
+        bytes[..data.len()].copy_from_slice(data);
+
+        let low = u128::from_le_bytes(bytes);
+
+        if self.sign {
+            low.wrapping_neg()
+        } else {
+            low
+        }
+    }
+
+    /// Returns the value as an `i128`, if it fits.
+    fn to_i128_checked(&self) -> Option<i128> {
+        let magnitude = self.magnitude_u128()?;
+
+        if self.sign {
+            // The magnitude of `i128::MIN` does not fit in an `i128`.
+            (magnitude <= i128::MIN.unsigned_abs()).then(|| (magnitude as i128).wrapping_neg())
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+}
+
+macro_rules! impl_conversions {
+    ($($primitive:ident => $wrapping:ident),*) => {
+        $(
+            impl From<$primitive> for BigInt {
+                fn from(value: $primitive) -> Self {
+                    BigInt::from_primitive(value)
+                }
+            }
+
+            impl TryFrom<&BigInt> for $primitive {
+                type Error = TryFromBigIntError;
+
+                fn try_from(value: &BigInt) -> Result<Self, Self::Error> {
+                    let error = TryFromBigIntError {
+                        target: stringify!($primitive),
+                    };
+
+                    let result = if value.sign {
+                        value.to_i128_checked().and_then(|value| $primitive::try_from(value).ok())
+                    } else {
+                        value.magnitude_u128().and_then(|value| $primitive::try_from(value).ok())
+                    };
+
+                    result.ok_or(error)
+                }
+            }
+
+            impl TryFrom<BigInt> for $primitive {
+                type Error = TryFromBigIntError;
+
+                fn try_from(value: BigInt) -> Result<Self, Self::Error> {
+                    $primitive::try_from(&value)
+                }
+            }
+
+            impl BigInt {
+                #[doc = concat!(
+                    "Returns the value truncated to a `", stringify!($primitive), "`, ",
+                    "wrapping around in two's complement like an `as` cast."
+                )]
+                pub fn $wrapping(&self) -> $primitive {
+                    self.low_u128() as $primitive
+                }
+            }
+        )*
+    };
+}
+
+impl_conversions!(
+    u8 => to_u8_wrapping,
+    u16 => to_u16_wrapping,
+    u32 => to_u32_wrapping,
+    u64 => to_u64_wrapping,
+    u128 => to_u128_wrapping,
+    usize => to_usize_wrapping,
+    i8 => to_i8_wrapping,
+    i16 => to_i16_wrapping,
+    i32 => to_i32_wrapping,
+    i64 => to_i64_wrapping,
+    i128 => to_i128_wrapping,
+    isize => to_isize_wrapping
+);
+
+impl From<bool> for BigInt {
+    fn from(value: bool) -> Self {
+        BigInt::from(value as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::number;
+    use crate::{BigInt, TryFromBigIntError};
+
+    mod from {
+        use super::*;
+
+        #[test]
+        fn should_convert_primitives_to_bigint() {
+            // When / Then
+            assert_eq!(BigInt::from(u64::MAX), number("18446744073709551615"));
+            assert_eq!(
+                BigInt::from(i128::MIN),
+                number("-170141183460469231731687303715884105728")
+            );
+            assert_eq!(BigInt::from(-1i8), number("-1"));
+            assert_eq!(BigInt::from(0usize), number("0"));
+        }
+
+        #[test]
+        fn should_convert_booleans_to_bigint() {
+            // When / Then
+            assert_eq!(BigInt::from(true), number("1"));
+            assert_eq!(BigInt::from(false), number("0"));
+        }
+    }
+
+    mod try_from {
+        use super::*;
+
+        #[test]
+        fn should_convert_values_in_range() {
+            // Given
+            let a = number("18446744073709551615");
+            let b = number("-170141183460469231731687303715884105728");
+
+            // When / Then
+            assert_eq!(u64::try_from(&a), Ok(u64::MAX));
+            assert_eq!(i128::try_from(&b), Ok(i128::MIN));
+            assert_eq!(i8::try_from(number("-128")), Ok(i8::MIN));
+            assert_eq!(u8::try_from(number("0")), Ok(0));
+        }
+
+        #[test]
+        fn should_reject_values_out_of_range() {
+            // Given
+            let a = number("18446744073709551616");
+            let b = number("-1");
+
+            // When
+            let too_big = u64::try_from(&a);
+            let negative = u32::try_from(&b);
+
+            // Then
+            assert_eq!(too_big.unwrap_err().target(), "u64");
+            assert_eq!(negative.unwrap_err().target(), "u32");
+            assert!(i8::try_from(number("128")).is_err());
+            assert!(i128::try_from(number("-170141183460469231731687303715884105729")).is_err());
+        }
+
+        #[test]
+        fn should_describe_the_overflow() {
+            // Given
+            let error: TryFromBigIntError = i16::try_from(number("40000")).unwrap_err();
+
+            // When
+            let message = error.to_string();
+
+            // Then
+            assert_eq!(message, "BigInt out of range for type i16");
+        }
+    }
+
+    mod wrapping {
+        use super::*;
+
+        #[test]
+        fn should_truncate_like_an_as_cast() {
+            // Given
+            let a = number("18446744073709551617");
+            let b = number("-1");
+            let c = number("-340282366920938463463374607431768211457");
+
+            // When / Then
+            assert_eq!(a.to_u64_wrapping(), 1);
+            assert_eq!(a.to_u8_wrapping(), 1);
+            assert_eq!(b.to_u32_wrapping(), u32::MAX);
+            assert_eq!(b.to_i64_wrapping(), -1);
+            assert_eq!(c.to_i128_wrapping(), -1);
+        }
+    }
+}
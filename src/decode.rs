@@ -0,0 +1,72 @@
+//! Errors of the decoders of binary wire formats.
+
+/// What went wrong while decoding a `BigInt` from a binary format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    /// The input ends before the encoded value does.
+    Truncated,
+    /// The value is introduced by a tag or type other than the expected one.
+    UnexpectedTag,
+    /// A length field is malformed or out of range.
+    InvalidLength,
+    /// The encoding is valid but not the shortest one, which a strict decoder rejects.
+    NonMinimal,
+}
+
+/// The error returned when a `BigInt` cannot be decoded from a binary format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    /// What went wrong.
+    kind: DecodeErrorKind,
+
+    /// Byte offset in the input where the problem was found.
+    position: usize,
+}
+
+impl DecodeError {
+    pub(crate) fn new(kind: DecodeErrorKind, position: usize) -> Self {
+        DecodeError { kind, position }
+    }
+
+    /// Returns what went wrong.
+    pub fn kind(&self) -> DecodeErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte offset in the input where the problem was found.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let description = match self.kind {
+            DecodeErrorKind::Truncated => "input truncated",
+            DecodeErrorKind::UnexpectedTag => "unexpected tag",
+            DecodeErrorKind::InvalidLength => "invalid length",
+            DecodeErrorKind::NonMinimal => "non-minimal encoding",
+        };
+
+        write!(formatter, "{} at byte {}", description, self.position)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Returns `length` bytes of `data` from `position`, or a truncation error.
+pub(crate) fn take(data: &[u8], position: usize, length: usize) -> Result<&[u8], DecodeError> {
+    data.get(position..)
+        .and_then(|rest| rest.get(..length))
+        .ok_or(DecodeError::new(DecodeErrorKind::Truncated, data.len()))
+}
+
+/// Whether big-endian two's complement bytes have no leading byte that only extends the sign
+/// of the next one.
+pub(crate) fn is_minimal_twos_complement(bytes: &[u8]) -> bool {
+    match bytes {
+        [0x00, next, ..] => next & 0x80 != 0,
+        [0xFF, next, ..] => next & 0x80 == 0,
+        _ => true,
+    }
+}
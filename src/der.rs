@@ -0,0 +1,209 @@
+//! ASN.1 INTEGER values in the Distinguished Encoding Rules (DER) and, for decoding, the more
+//! permissive Basic Encoding Rules (BER).
+
+use crate::decode::{self, DecodeError, DecodeErrorKind};
+use crate::BigInt;
+
+/// The tag of a universal, primitive INTEGER.
+const INTEGER_TAG: u8 = 0x02;
+
+impl BigInt {
+    /// Returns the value as a DER INTEGER: the tag, the length and the minimal big-endian two's
+    /// complement contents.
+    pub fn to_der(&self) -> Vec<u8> {
+        let contents = self.to_signed_bytes_be();
+        let mut result = vec![INTEGER_TAG];
+
+        if contents.len() < 0x80 {
+            result.push(contents.len() as u8);
+        } else {
+            let length = contents.len().to_be_bytes();
+            let length = &length[length.iter().take_while(|&&byte| byte == 0).count()..];
+
+            result.push(0x80 | length.len() as u8);
+            result.extend_from_slice(length);
+        }
+
+        result.extend_from_slice(&contents);
+
+        result
+    }
+
+    /// Decode a DER INTEGER from the start of `data`, rejecting non-minimal lengths and
+    /// contents. Returns the value and the number of bytes read.
+    pub fn from_der(data: &[u8]) -> Result<(Self, usize), DecodeError> {
+        decode_integer(data, true)
+    }
+
+    /// Decode a BER INTEGER from the start of `data`, accepting non-minimal lengths and
+    /// contents. Returns the value and the number of bytes read.
+    pub fn from_ber(data: &[u8]) -> Result<(Self, usize), DecodeError> {
+        decode_integer(data, false)
+    }
+}
+
+/// Decode an INTEGER, checking that it is the shortest encoding if `strict`.
+fn decode_integer(data: &[u8], strict: bool) -> Result<(BigInt, usize), DecodeError> {
+    let tag = decode::take(data, 0, 1)?[0];
+
+    if tag != INTEGER_TAG {
+        return Err(DecodeError::new(DecodeErrorKind::UnexpectedTag, 0));
+    }
+
+    let (length, header_length) = decode_length(data, strict)?;
+
+    // An INTEGER has at least one content byte.
+    if length == 0 {
+        return Err(DecodeError::new(DecodeErrorKind::InvalidLength, 1));
+    }
+
+    let contents = decode::take(data, header_length, length)?;
+
+    if strict && !decode::is_minimal_twos_complement(contents) {
+        return Err(DecodeError::new(DecodeErrorKind::NonMinimal, header_length));
+    }
+
+    Ok((
+        BigInt::from_signed_bytes_be(contents),
+        header_length + length,
+    ))
+}
+
+/// Returns the length of the contents and the length of the tag and length fields.
+fn decode_length(data: &[u8], strict: bool) -> Result<(usize, usize), DecodeError> {
+    let first = decode::take(data, 1, 1)?[0];
+
+    if first < 0x80 {
+        return Ok((first as usize, 2));
+    }
+
+    // 0x80 is the indefinite length, not allowed for a primitive value; 0xFF is reserved.
+    let count = (first & 0x7F) as usize;
+
+    if count == 0 || first == 0xFF {
+        return Err(DecodeError::new(DecodeErrorKind::InvalidLength, 1));
+    }
+
+    let bytes = decode::take(data, 2, count)?;
+    let significant = &bytes[bytes.iter().take_while(|&&byte| byte == 0).count()..];
+
+    if significant.len() > size_of::<usize>() {
+        return Err(DecodeError::new(DecodeErrorKind::InvalidLength, 2));
+    }
+
+    let length = significant
+        .iter()
+        .fold(0usize, |length, &byte| length << 8 | byte as usize);
+
+    if strict && (significant.len() < count || length < 0x80) {
+        return Err(DecodeError::new(DecodeErrorKind::NonMinimal, 1));
+    }
+
+    Ok((length, 2 + count))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigInt, DecodeErrorKind};
+
+    mod encode {
+        use super::*;
+
+        #[test]
+        fn should_write_minimal_contents() {
+            // When / Then
+            assert_eq!(BigInt::from(0u8).to_der(), vec![0x02, 0x01, 0x00]);
+            assert_eq!(BigInt::from(127u8).to_der(), vec![0x02, 0x01, 0x7F]);
+            assert_eq!(BigInt::from(128u8).to_der(), vec![0x02, 0x02, 0x00, 0x80]);
+            assert_eq!(BigInt::from(-128i16).to_der(), vec![0x02, 0x01, 0x80]);
+            assert_eq!(BigInt::from(-129i16).to_der(), vec![0x02, 0x02, 0xFF, 0x7F]);
+        }
+
+        #[test]
+        fn should_write_long_lengths() {
+            // Given: a 200-byte magnitude with the top bit set, so 201 content bytes.
+            let value = (BigInt::from(1u8) << 1600) - 1u8;
+
+            // When
+            let encoded = value.to_der();
+
+            // Then
+            assert_eq!(encoded[..4], [0x02, 0x81, 201, 0x00]);
+            assert_eq!(encoded.len(), 3 + 201);
+            assert_eq!(BigInt::from_der(&encoded), Ok((value, 204)));
+        }
+    }
+
+    mod decode {
+        use super::*;
+
+        #[test]
+        fn should_report_the_bytes_read() {
+            // Given: a serial number followed by the next field.
+            let data = [0x02, 0x03, 0x01, 0x00, 0x01, 0x30];
+
+            // When
+            let result = BigInt::from_der(&data);
+
+            // Then
+            assert_eq!(result, Ok((BigInt::from(65537u32), 5)));
+        }
+
+        #[test]
+        fn should_round_trip_signed_values() {
+            for value in [0i128, 1, -1, 255, -256, i128::MIN, i128::MAX] {
+                // Given
+                let value = BigInt::from(value);
+
+                // When
+                let encoded = value.to_der();
+
+                // Then
+                assert_eq!(BigInt::from_der(&encoded), Ok((value, encoded.len())));
+            }
+        }
+
+        #[test]
+        fn should_reject_non_minimal_encodings_when_strict() {
+            for (data, expected) in [
+                (&[0x02, 0x02, 0x00, 0x7F][..], 127),
+                (&[0x02, 0x02, 0xFF, 0x80], -128),
+                (&[0x02, 0x81, 0x01, 0x05], 5),
+                (&[0x02, 0x82, 0x00, 0x01, 0x05], 5),
+            ] {
+                // When
+                let strict = BigInt::from_der(data).unwrap_err();
+                let lenient = BigInt::from_ber(data);
+
+                // Then
+                assert_eq!(strict.kind(), DecodeErrorKind::NonMinimal);
+                assert_eq!(lenient, Ok((BigInt::from(expected), data.len())));
+            }
+        }
+
+        #[test]
+        fn should_reject_malformed_input() {
+            // When / Then
+            let error = |data: &[u8]| BigInt::from_ber(data).unwrap_err();
+
+            assert_eq!(
+                error(&[0x04, 0x01, 0x00]).kind(),
+                DecodeErrorKind::UnexpectedTag
+            );
+            assert_eq!(error(&[0x02, 0x00]).kind(), DecodeErrorKind::InvalidLength);
+            assert_eq!(
+                error(&[0x02, 0x80, 0x01, 0x00, 0x00]).kind(),
+                DecodeErrorKind::InvalidLength
+            );
+            assert_eq!(
+                error(&[0x02, 0x03, 0x01]).kind(),
+                DecodeErrorKind::Truncated
+            );
+            assert_eq!(error(&[0x02]).kind(), DecodeErrorKind::Truncated);
+            assert_eq!(
+                error(&[0x02, 0x03, 0x01]).to_string(),
+                "input truncated at byte 3"
+            );
+        }
+    }
+}
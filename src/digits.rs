@@ -0,0 +1,498 @@
+//! Digit counts, leading and trailing digits, computed without writing out every digit.
+//!
+//! The quotient of a number by a power of the radix is first estimated from the top bits of
+//! both. Only when the estimate is too close to call (near an exact multiple, or a tie for
+//! rounding) is the exact power computed and divided.
+
+use crate::magnitude;
+use crate::radix;
+use crate::RoundingMode;
+use crate::{Base, BigInt};
+use std::cmp::Ordering;
+
+/// Bits kept below the unit of an estimated quotient.
+const GUARD_BITS: usize = 64;
+
+/// Bound, in units of the guard bits, of the error of an estimated quotient.
+const ESTIMATE_ERROR: u8 = 3;
+
+/// Where the part discarded by a division falls, relative to the divisor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Fraction {
+    /// Nothing was discarded.
+    Zero,
+    /// Less than half of the divisor was discarded.
+    BelowHalf,
+    /// Exactly half of the divisor was discarded.
+    Half,
+    /// More than half of the divisor was discarded.
+    AboveHalf,
+}
+
+impl Fraction {
+    /// Returns `true` if a magnitude followed by this fraction rounds away from zero with
+    /// `mode`. `odd` tells if the magnitude is odd, for ties to even.
+    pub(crate) fn rounds_up(self, mode: RoundingMode, negative: bool, odd: bool) -> bool {
+        match (mode, self) {
+            (_, Fraction::Zero) => false,
+            (RoundingMode::TowardZero, _) => false,
+            (RoundingMode::AwayFromZero, _) => true,
+            (RoundingMode::Floor, _) => negative,
+            (RoundingMode::Ceiling, _) => !negative,
+            (_, Fraction::BelowHalf) => false,
+            (_, Fraction::AboveHalf) => true,
+            (RoundingMode::HalfAwayFromZero, Fraction::Half) => true,
+            (RoundingMode::HalfEven, Fraction::Half) => odd,
+        }
+    }
+}
+
+impl BigInt {
+    /// Returns the number of decimal digits of the magnitude. Zero has one digit.
+    pub fn decimal_digit_count(&self) -> usize {
+        self.digit_count(10)
+    }
+
+    /// Returns the first `count` digits of the magnitude in the provided base, or all of them
+    /// if there are fewer.
+    pub fn leading_digits(&self, count: usize, base: Base) -> String {
+        if count == 0 {
+            return String::new();
+        }
+
+        let radix = base.to_radix();
+
+        self.leading_digits_of(count, radix, self.digit_count(radix))
+    }
+
+    /// Returns the first `count` digits of the magnitude in `radix`, which has `total` digits.
+    pub(crate) fn leading_digits_of(&self, count: usize, radix: u8, total: usize) -> String {
+        let (leading, _) = self.scale_down(radix, total.saturating_sub(count));
+
+        leading.to_digit_string(radix, true)
+    }
+
+    /// Returns the last `count` digits of the magnitude in the provided base, zeros included,
+    /// or all of them if there are fewer.
+    ///
+    /// They come from a single remainder by `radix^count`, without counting the digits.
+    pub fn trailing_digits(&self, count: usize, base: Base) -> String {
+        if count == 0 {
+            return String::new();
+        }
+
+        let radix = base.to_radix();
+
+        // Beyond this many digits the power would only be larger than the magnitude.
+        let most = (self.bit_length() as f64 / (radix as f64).log2()) as usize + 2;
+
+        if count > most {
+            return self.abs().to_digit_string(radix, true);
+        }
+
+        let (quotient, trailing) =
+            magnitude::div_rem(&self.data, &BigInt::power_of(radix, count).data);
+        let trailing = BigInt::from_magnitude(trailing, false).to_digit_string(radix, true);
+
+        // A zero quotient means the magnitude has no more than `count` digits.
+        if quotient.is_empty() {
+            trailing
+        } else {
+            format!("{:0>count$}", trailing)
+        }
+    }
+
+    /// Returns the digit values of the magnitude in the provided base, most significant first.
+    /// Zero is a single `0` digit.
+    ///
+    /// The digits are written chunk by chunk as the iterator is consumed, from either end. Call
+    /// `rev()` on the iterator for the least significant digit first.
+    pub fn digits(&self, base: Base) -> impl DoubleEndedIterator<Item = u8> + ExactSizeIterator {
+        let radix = base.to_radix();
+
+        radix::digits(&self.data, radix, self.digit_count(radix))
+    }
+
+    /// Returns the sum of the digit values of the magnitude in the provided base.
+    pub fn digit_sum(&self, base: Base) -> u64 {
+        self.digits(base).map(|digit| digit as u64).sum()
+    }
+
+    /// Returns `radix^exponent`.
+    pub(crate) fn power_of(radix: u8, exponent: usize) -> BigInt {
+        let mut result = BigInt::from(1u8);
+
+        for bit in (0..usize::BITS - exponent.leading_zeros()).rev() {
+            result = &result * &result;
+
+            if exponent >> bit & 1 == 1 {
+                result *= radix;
+            }
+        }
+
+        result
+    }
+
+    /// Returns the number of digits of the magnitude in `radix`. Zero has one digit.
+    pub(crate) fn digit_count(&self, radix: u8) -> usize {
+        let bits = self.bit_length();
+
+        if bits == 0 {
+            return 1;
+        }
+
+        // The digit count of 2^(bits - 1), give or take the rounding of the logarithm.
+        let mut count = ((bits - 1) as f64 / (radix as f64).log2()) as usize + 1;
+
+        // The magnitude has more than `count` digits when it is at least radix^count.
+        while count > 1 && self.scale_down(radix, count - 1).0.is_zero() {
+            count -= 1;
+        }
+
+        while !self.scale_down(radix, count).0.is_zero() {
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Returns the magnitude divided by `radix^shift` and rounded down, with where the
+    /// discarded fraction falls.
+    pub(crate) fn scale_down(&self, radix: u8, shift: usize) -> (BigInt, Fraction) {
+        let magnitude = self.abs();
+
+        if shift == 0 {
+            return (magnitude, Fraction::Zero);
+        }
+
+        // Enough bits to hold the quotient and the guard bits, with a margin for the error of
+        // the estimated power.
+        let log2_radix = (radix as f64).log2();
+        let quotient_bits = (self.bit_length() as f64 - shift as f64 * log2_radix).max(0.0);
+        let precision = quotient_bits as usize + 2 * GUARD_BITS;
+
+        if self.bit_length() > precision {
+            if let Some(result) = magnitude.estimate_scale_down(radix, shift, precision) {
+                return result;
+            }
+        }
+
+        let divisor = BigInt::power_of(radix, shift);
+        let (quotient, remainder) = magnitude::div_rem(&magnitude.data, &divisor.data);
+
+        let fraction = if remainder.is_empty() {
+            Fraction::Zero
+        } else {
+            match magnitude::compare(&magnitude::shl(&remainder, 1), &divisor.data) {
+                Ordering::Less => Fraction::BelowHalf,
+                Ordering::Equal => Fraction::Half,
+                Ordering::Greater => Fraction::AboveHalf,
+            }
+        };
+
+        (BigInt::from_magnitude(quotient, false), fraction)
+    }
+
+    /// Estimate `self / radix^shift` for a non-negative `self` from the top `precision` bits of
+    /// both operands. Returns `None` when the error of the estimate leaves the result open.
+    fn estimate_scale_down(
+        &self,
+        radix: u8,
+        shift: usize,
+        precision: usize,
+    ) -> Option<(BigInt, Fraction)> {
+        let top_shift = self.bit_length() - precision;
+        let top = self >> top_shift;
+
+        let (power, power_shift) = power_estimate(radix, shift, precision);
+
+        // top * 2^top_shift / (power * 2^power_shift), with GUARD_BITS fractional bits.
+        let scale = top_shift as i64 - power_shift + GUARD_BITS as i64;
+
+        let numerator = if scale >= 0 {
+            top << scale as usize
+        } else {
+            top >> scale.unsigned_abs() as usize
+        };
+
+        let estimate = &numerator / &power;
+
+        let low = &estimate - ESTIMATE_ERROR;
+        let high = &estimate + ESTIMATE_ERROR;
+
+        if low.is_negative() {
+            return None;
+        }
+
+        let quotient = &low >> GUARD_BITS;
+
+        if (&high >> GUARD_BITS) != quotient {
+            return None;
+        }
+
+        let (fraction_low, fraction_high) = (low.to_u64_wrapping(), high.to_u64_wrapping());
+        let half = 1u64 << (GUARD_BITS - 1);
+
+        let fraction = if fraction_low == 0 {
+            return None;
+        } else if fraction_high < half {
+            Fraction::BelowHalf
+        } else if fraction_low > half {
+            Fraction::AboveHalf
+        } else {
+            return None;
+        };
+
+        Some((quotient, fraction))
+    }
+}
+
+/// Returns a lower bound `power * 2^power_shift` of `radix^exponent`, where `power` has at most
+/// `precision` bits and a relative error below `2^-(precision - 4)`.
+fn power_estimate(radix: u8, exponent: usize, precision: usize) -> (BigInt, i64) {
+    // Each squaring doubles the relative error, so keep one more bit per squaring.
+    let steps = (usize::BITS - exponent.leading_zeros()) as usize;
+    let precision = precision + steps;
+
+    let mut power = BigInt::from(1u8);
+    let mut power_shift = 0i64;
+
+    for bit in (0..steps).rev() {
+        power = &power * &power;
+        power_shift *= 2;
+
+        if exponent >> bit & 1 == 1 {
+            power *= radix;
+        }
+
+        let excess = power.bit_length().saturating_sub(precision);
+
+        power >>= excess;
+        power_shift += excess as i64;
+    }
+
+    (power, power_shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::digits::Fraction;
+    use crate::testing::number;
+    use crate::{Base, BigInt, RoundingMode};
+
+    mod digit_count {
+        use super::*;
+
+        #[test]
+        fn should_count_digits_around_powers_of_the_radix() {
+            // Given
+            let power = BigInt::power_of(10, 500);
+
+            // When / Then
+            assert_eq!(BigInt::from(0u8).digit_count(10), 1);
+            assert_eq!(BigInt::from(-999i16).digit_count(10), 3);
+            assert_eq!(power.digit_count(10), 501);
+            assert_eq!((&power - 1u8).digit_count(10), 500);
+            assert_eq!(BigInt::from(255u8).digit_count(16), 2);
+        }
+    }
+
+    mod leading_and_trailing {
+        use super::*;
+
+        #[test]
+        fn should_return_the_ends_of_the_magnitude() {
+            // Given
+            let value = -number("123450000000000000000000000000000000000000000000006789");
+
+            // When / Then
+            assert_eq!(value.decimal_digit_count(), 54);
+            assert_eq!(value.leading_digits(5, Base::Decimal), "12345");
+            assert_eq!(value.trailing_digits(6, Base::Decimal), "006789");
+            assert_eq!(value.trailing_digits(0, Base::Decimal), "");
+        }
+
+        #[test]
+        fn should_work_in_any_base() {
+            // Given
+            let value = BigInt::from(0xDEAD_BEEFu32);
+
+            // When / Then
+            assert_eq!(value.leading_digits(3, Base::Hexadecimal), "DEA");
+            assert_eq!(value.trailing_digits(3, Base::Hexadecimal), "EEF");
+            assert_eq!(value.leading_digits(4, Base::Binary), "1101");
+        }
+
+        #[test]
+        fn should_return_all_the_digits_of_short_values() {
+            // Given
+            let value = BigInt::from(42u8);
+
+            // When / Then
+            assert_eq!(value.leading_digits(5, Base::Decimal), "42");
+            assert_eq!(value.trailing_digits(5, Base::Decimal), "42");
+            assert_eq!(BigInt::from(0u8).trailing_digits(3, Base::Octal), "0");
+        }
+
+        #[test]
+        fn should_pad_the_trailing_digits_only_inside_the_number() {
+            // Given
+            let value = number("100000");
+
+            // When / Then
+            assert_eq!(value.trailing_digits(5, Base::Decimal), "00000");
+            assert_eq!(value.trailing_digits(6, Base::Decimal), "100000");
+            assert_eq!(value.trailing_digits(7, Base::Decimal), "100000");
+            assert_eq!(value.trailing_digits(usize::MAX, Base::Decimal), "100000");
+        }
+    }
+
+    mod iterator {
+        use super::*;
+
+        #[test]
+        fn should_iterate_in_both_orders() {
+            // Given
+            let value = BigInt::from(-0x1A2Bi16);
+
+            // When
+            let big_endian: Vec<u8> = value.digits(Base::Hexadecimal).collect();
+            let little_endian: Vec<u8> = value.digits(Base::Hexadecimal).rev().collect();
+
+            // Then
+            assert_eq!(big_endian, vec![1, 10, 2, 11]);
+            assert_eq!(little_endian, vec![11, 2, 10, 1]);
+            assert_eq!(BigInt::from(0u8).digits(Base::Decimal).len(), 1);
+        }
+
+        #[test]
+        fn should_validate_a_luhn_check_digit() {
+            // Given
+            let value = number("79927398713");
+
+            // When
+            let sum: u32 = value
+                .digits(Base::Decimal)
+                .rev()
+                .enumerate()
+                .map(|(index, digit)| match (index % 2, digit as u32 * 2) {
+                    (0, _) => digit as u32,
+                    (_, doubled) if doubled > 9 => doubled - 9,
+                    (_, doubled) => doubled,
+                })
+                .sum();
+
+            // Then
+            assert_eq!(sum % 10, 0);
+        }
+
+        #[test]
+        fn should_match_the_digit_string_of_long_values() {
+            // Given: values long enough to split around several powers.
+            let values = [
+                BigInt::power_of(3, 20000),
+                BigInt::power_of(10, 5000),
+                BigInt::power_of(10, 5000) - 1u8,
+                BigInt::from(0u8),
+            ];
+
+            for value in &values {
+                for radix in [3, 10, 16, 36] {
+                    // When
+                    let base = Base::from_radix(radix).unwrap();
+                    let digits: Vec<u8> = value.digits(base).collect();
+                    let mut reversed: Vec<u8> = value.digits(base).rev().collect();
+                    reversed.reverse();
+
+                    // Then
+                    assert_eq!(digits, value.to_digits(radix));
+                    assert_eq!(reversed, digits);
+                    assert_eq!(value.digits(base).len(), digits.len());
+                }
+            }
+        }
+
+        #[test]
+        fn should_meet_in_the_middle() {
+            // Given
+            let value = BigInt::power_of(7, 30000) + 1u8;
+            let expected = value.to_digits(10);
+            let mut digits = value.digits(Base::Decimal);
+
+            // When
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+
+            while let Some(digit) = digits.next() {
+                front.push(digit);
+
+                if let Some(digit) = digits.next_back() {
+                    back.push(digit);
+                }
+            }
+
+            // Then
+            back.reverse();
+            front.extend(back);
+            assert_eq!(front, expected);
+            assert_eq!(digits.len(), 0);
+        }
+
+        #[test]
+        fn should_sum_the_digits() {
+            // When / Then
+            assert_eq!(number("-99999").digit_sum(Base::Decimal), 45);
+            assert_eq!(BigInt::from(u64::MAX).digit_sum(Base::Binary), 64);
+            assert_eq!(BigInt::power_of(10, 1000).digit_sum(Base::Decimal), 1);
+        }
+    }
+
+    mod scale_down {
+        use super::*;
+
+        #[test]
+        fn should_match_the_exact_division() {
+            // Given: 3^2000, whose quotients are estimated.
+            let value = BigInt::power_of(3, 2000);
+
+            for shift in [1, 10, 400, 900, 954] {
+                // When
+                let (quotient, fraction) = value.scale_down(10, shift);
+
+                // Then
+                let divisor = BigInt::power_of(10, shift);
+                assert_eq!(quotient, &value / &divisor);
+                assert_ne!(fraction, Fraction::Zero);
+            }
+        }
+
+        #[test]
+        fn should_detect_exact_and_tied_fractions() {
+            // Given
+            let exact = number("12300") * BigInt::power_of(10, 300);
+            let tie = number("12350") * BigInt::power_of(10, 300);
+
+            // When / Then
+            assert_eq!(exact.scale_down(10, 302), (number("123"), Fraction::Zero));
+            assert_eq!(tie.scale_down(10, 302), (number("123"), Fraction::Half));
+            assert_eq!(
+                (-&tie - 1u8).scale_down(10, 302),
+                (number("123"), Fraction::AboveHalf)
+            );
+        }
+    }
+
+    mod rounding {
+        use super::*;
+
+        #[test]
+        fn should_round_ties_with_the_mode() {
+            // When / Then
+            assert!(Fraction::Half.rounds_up(RoundingMode::HalfAwayFromZero, false, false));
+            assert!(!Fraction::Half.rounds_up(RoundingMode::HalfEven, false, false));
+            assert!(Fraction::Half.rounds_up(RoundingMode::HalfEven, true, true));
+            assert!(Fraction::BelowHalf.rounds_up(RoundingMode::Floor, true, false));
+            assert!(!Fraction::Zero.rounds_up(RoundingMode::AwayFromZero, false, false));
+        }
+    }
+}
@@ -0,0 +1,308 @@
+//! Conversions between `BigInt` and the floating point types.
+
+use crate::magnitude;
+use crate::BigInt;
+
+/// How to round a value that falls between two integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round toward zero, discarding the fractional part.
+    TowardZero,
+    /// Round away from zero.
+    AwayFromZero,
+    /// Round toward negative infinity.
+    Floor,
+    /// Round toward positive infinity.
+    Ceiling,
+    /// Round to the nearest integer, ties away from zero.
+    HalfAwayFromZero,
+    /// Round to the nearest integer, ties to the even neighbour.
+    HalfEven,
+}
+
+/// The error returned when a floating point value has no integer counterpart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromFloatError {
+    /// The value is NaN.
+    NaN,
+    /// The value is positive or negative infinity.
+    Infinite,
+}
+
+impl std::fmt::Display for FromFloatError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            FromFloatError::NaN => write!(formatter, "cannot convert NaN to BigInt"),
+            FromFloatError::Infinite => write!(formatter, "cannot convert infinity to BigInt"),
+        }
+    }
+}
+
+impl std::error::Error for FromFloatError {}
+
+impl BigInt {
+    /// Returns the number of significant bits of the magnitude.
+    pub(crate) fn bit_length(&self) -> usize {
+        let data = magnitude::significant(&self.data);
+
+        match data.last() {
+            Some(&top) => data.len() * 8 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Returns the top 64 bits of the magnitude, with the lowest bit set if any of the
+    /// discarded bits is set, and the power of two the result must be scaled by.
+    ///
+    /// The sticky bit keeps a single rounding of the 64 bits to a narrower mantissa correct.
+    fn top_bits(&self) -> (u64, i32) {
+        let length = self.bit_length();
+
+        if length <= 64 {
+            return (self.magnitude_low_u64(), 0);
+        }
+
+        let shift = length - 64;
+        let top = magnitude::shr(&self.data, shift);
+
+        let mut bytes = [0u8; 8];
+        bytes[..top.len()].copy_from_slice(&top);
+
+        let sticky = magnitude::has_low_bits(&self.data, shift) as u64;
+
+        (u64::from_le_bytes(bytes) | sticky, shift as i32)
+    }
+
+    /// Returns the lowest 64 bits of the magnitude.
+    fn magnitude_low_u64(&self) -> u64 {
+        let data = &self.data[..self.data.len().min(8)];
+
+        let mut bytes = [0u8; 8];
+        bytes[..data.len()].copy_from_slice(data);
+
+        u64::from_le_bytes(bytes)
+    }
+
+    /// Returns the nearest `f64`, rounding ties to even. Values too large for an `f64` become
+    /// infinite.
+    pub fn to_f64(&self) -> f64 {
+        let (top, exponent) = self.top_bits();
+
+        // Scaling by a power of two is exact, so the only rounding is the one in `as`.
+        let scale = if exponent > f64::MAX_EXP - 1 {
+            f64::INFINITY
+        } else {
+            f64::from_bits(((exponent + 1023) as u64) << 52)
+        };
+
+        let value = top as f64 * scale;
+
+        if self.is_negative() {
+            -value
+        } else {
+            value
+        }
+    }
+
+    /// Returns the nearest `f32`, rounding ties to even. Values too large for an `f32` become
+    /// infinite.
+    pub fn to_f32(&self) -> f32 {
+        let (top, exponent) = self.top_bits();
+
+        let scale = if exponent > f32::MAX_EXP - 1 {
+            f32::INFINITY
+        } else {
+            f32::from_bits(((exponent + 127) as u32) << 23)
+        };
+
+        let value = top as f32 * scale;
+
+        if self.is_negative() {
+            -value
+        } else {
+            value
+        }
+    }
+
+    /// Create a new `BigInt` from an `f64`, rounding the fractional part with `mode`.
+    pub fn from_f64(value: f64, mode: RoundingMode) -> Result<Self, FromFloatError> {
+        if value.is_nan() {
+            return Err(FromFloatError::NaN);
+        }
+
+        if value.is_infinite() {
+            return Err(FromFloatError::Infinite);
+        }
+
+        let value = match mode {
+            RoundingMode::TowardZero => value.trunc(),
+            RoundingMode::AwayFromZero if value.fract() != 0.0 => value.trunc() + value.signum(),
+            RoundingMode::AwayFromZero => value,
+            RoundingMode::Floor => value.floor(),
+            RoundingMode::Ceiling => value.ceil(),
+            RoundingMode::HalfAwayFromZero => value.round(),
+            RoundingMode::HalfEven => value.round_ties_even(),
+        };
+
+        // An integral `f64` is `mantissa * 2^exponent` exactly.
+        let bits = value.to_bits();
+        let biased_exponent = ((bits >> 52) & 0x7FF) as i32;
+
+        if biased_exponent == 0 {
+            // Zero: subnormals are all rounded away by now.
+            return Ok(BigInt::from(0u8));
+        }
+
+        let mantissa = BigInt::from(bits & ((1 << 52) - 1) | 1 << 52);
+        let exponent = biased_exponent - 1075;
+
+        let result = if exponent >= 0 {
+            mantissa << exponent as usize
+        } else {
+            mantissa >> exponent.unsigned_abs() as usize
+        };
+
+        Ok(if value < 0.0 { -result } else { result })
+    }
+
+    /// Create a new `BigInt` from an `f32`, rounding the fractional part with `mode`.
+    pub fn from_f32(value: f32, mode: RoundingMode) -> Result<Self, FromFloatError> {
+        // Every `f32` is exactly representable as an `f64`.
+        BigInt::from_f64(value as f64, mode)
+    }
+}
+
+/// Truncates toward zero, like an `as` cast. Use [`BigInt::from_f64`] to pick the rounding.
+impl TryFrom<f64> for BigInt {
+    type Error = FromFloatError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        BigInt::from_f64(value, RoundingMode::TowardZero)
+    }
+}
+
+/// Truncates toward zero, like an `as` cast. Use [`BigInt::from_f32`] to pick the rounding.
+impl TryFrom<f32> for BigInt {
+    type Error = FromFloatError;
+
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        BigInt::from_f32(value, RoundingMode::TowardZero)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::number;
+    use crate::{BigInt, FromFloatError, RoundingMode};
+
+    mod to_float {
+        use super::*;
+
+        #[test]
+        fn should_convert_small_values_exactly() {
+            // When / Then
+            assert_eq!(number("0").to_f64(), 0.0);
+            assert_eq!(number("-12345").to_f64(), -12345.0);
+            assert_eq!(number("16777217").to_f32(), 16777216.0);
+        }
+
+        #[test]
+        fn should_convert_negative_zero_to_positive_zero() {
+            // Given
+            let value = BigInt::from_bytes(vec![], true);
+
+            // When / Then
+            assert!(value.to_f64().is_sign_positive());
+            assert!(value.to_f32().is_sign_positive());
+        }
+
+        #[test]
+        fn should_round_half_to_even() {
+            // Given: 2^53 + 1 and 2^53 + 3 are halfway between two f64 values.
+            let a = number("9007199254740993");
+            let b = number("9007199254740995");
+
+            // When / Then
+            assert_eq!(a.to_f64(), 9007199254740992.0);
+            assert_eq!(b.to_f64(), 9007199254740996.0);
+        }
+
+        #[test]
+        fn should_round_with_bits_beyond_the_top_64() {
+            // Given: 2^100 + 2^47 + 1 is just above halfway between two f64 values.
+            let a = (BigInt::from(1u8) << 100) + (BigInt::from(1u8) << 47) + 1u8;
+
+            // When
+            let result = a.to_f64();
+
+            // Then
+            assert_eq!(result, 2f64.powi(100) + 2f64.powi(48));
+        }
+
+        #[test]
+        fn should_overflow_to_infinity() {
+            // Given
+            let a = BigInt::from(1u8) << 1024;
+            let b = -(BigInt::from(1u8) << 128);
+
+            // When / Then
+            assert_eq!(a.to_f64(), f64::INFINITY);
+            assert_eq!(b.to_f32(), f32::NEG_INFINITY);
+            assert_eq!((BigInt::from(1u8) << 1023).to_f64(), 2f64.powi(1023));
+        }
+    }
+
+    mod from_float {
+        use super::*;
+
+        #[test]
+        fn should_reject_nan_and_infinities() {
+            // When / Then
+            assert_eq!(BigInt::try_from(f64::NAN), Err(FromFloatError::NaN));
+            assert_eq!(
+                BigInt::try_from(f32::INFINITY),
+                Err(FromFloatError::Infinite)
+            );
+            assert_eq!(
+                BigInt::from_f64(f64::NEG_INFINITY, RoundingMode::Floor),
+                Err(FromFloatError::Infinite)
+            );
+        }
+
+        #[test]
+        fn should_round_with_the_requested_mode() {
+            // Given
+            let value = -2.5;
+
+            // When / Then
+            let round = |mode| BigInt::from_f64(value, mode).unwrap();
+
+            assert_eq!(round(RoundingMode::TowardZero), number("-2"));
+            assert_eq!(round(RoundingMode::AwayFromZero), number("-3"));
+            assert_eq!(round(RoundingMode::Floor), number("-3"));
+            assert_eq!(round(RoundingMode::Ceiling), number("-2"));
+            assert_eq!(round(RoundingMode::HalfAwayFromZero), number("-3"));
+            assert_eq!(round(RoundingMode::HalfEven), number("-2"));
+        }
+
+        #[test]
+        fn should_convert_large_values_exactly() {
+            // Given
+            let value = 1e30;
+
+            // When
+            let result = BigInt::try_from(value).unwrap();
+
+            // Then
+            assert_eq!(result, number("1000000000000000019884624838656"));
+            assert_eq!(BigInt::try_from(f64::MAX).unwrap().to_f64(), f64::MAX);
+        }
+
+        #[test]
+        fn should_convert_fractions_to_zero() {
+            // When / Then
+            assert_eq!(BigInt::try_from(0.75f32).unwrap(), number("0"));
+            assert_eq!(BigInt::try_from(-1e-300).unwrap(), number("0"));
+        }
+    }
+}
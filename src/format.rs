@@ -0,0 +1,455 @@
+//! Formatting of `BigInt` values, through the `std::fmt` traits or with [`FormatOptions`].
+
+use crate::{Base, BigInt, RoundingMode};
+
+/// Options for [`BigInt::to_string_with_options`].
+///
+/// By default the output is the same as [`BigInt::to_string`]: no prefix, no grouping and
+/// uppercase letter digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Separator written between groups of digits, if any.
+    group_separator: Option<char>,
+
+    /// Number of digits in a group.
+    group_size: usize,
+
+    /// Write the letter digits in uppercase.
+    uppercase: bool,
+
+    /// Pad the digits with leading zeros up to this count.
+    min_digits: usize,
+
+    /// Write a `+` before non-negative values.
+    explicit_plus: bool,
+
+    /// Write the `0x`, `0o` or `0b` prefix of the base.
+    prefix: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            group_separator: None,
+            group_size: 3,
+            uppercase: true,
+            min_digits: 0,
+            explicit_plus: false,
+            prefix: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Separate groups of digits with `separator`, counting from the least significant digit.
+    pub fn group_separator(mut self, separator: char) -> Self {
+        self.group_separator = Some(separator);
+        self
+    }
+
+    /// Put `size` digits in each group, 3 by default. A size of 0 disables the grouping.
+    pub fn group_size(mut self, size: usize) -> Self {
+        self.group_size = size;
+        self
+    }
+
+    /// Write the letter digits in uppercase, the default, or in lowercase.
+    pub fn uppercase(mut self, uppercase: bool) -> Self {
+        self.uppercase = uppercase;
+        self
+    }
+
+    /// Pad the digits with leading zeros so that there are at least `count` of them.
+    pub fn min_digits(mut self, count: usize) -> Self {
+        self.min_digits = count;
+        self
+    }
+
+    /// Write a `+` before zero and positive values.
+    pub fn explicit_plus(mut self, explicit_plus: bool) -> Self {
+        self.explicit_plus = explicit_plus;
+        self
+    }
+
+    /// Write the `0x`, `0o` or `0b` prefix after the sign, for the bases that have one.
+    ///
+    /// With `_` as the group separator, the output can be read back by
+    /// [`BigInt::from_literal`].
+    pub fn prefix(mut self, prefix: bool) -> Self {
+        self.prefix = prefix;
+        self
+    }
+}
+
+impl BigInt {
+    /// Returns the number encoded as a string in the provided base, laid out with `options`.
+    pub fn to_string_with_options(&self, base: Base, options: FormatOptions) -> String {
+        let radix = base.to_radix();
+        let digits = self.to_digit_string(radix, options.uppercase);
+
+        let mut result = String::new();
+
+        if self.is_negative() {
+            result.push('-');
+        } else if options.explicit_plus {
+            result.push('+');
+        }
+
+        if options.prefix {
+            result.push_str(match radix {
+                16 => "0x",
+                8 => "0o",
+                2 => "0b",
+                _ => "",
+            });
+        }
+
+        let padding = options.min_digits.saturating_sub(digits.len());
+        let count = padding + digits.len();
+
+        for (index, digit) in std::iter::repeat_n('0', padding)
+            .chain(digits.chars())
+            .enumerate()
+        {
+            let remaining = count - index;
+
+            if let Some(separator) = options.group_separator {
+                if index > 0 && remaining.is_multiple_of(options.group_size) {
+                    result.push(separator);
+                }
+            }
+
+            result.push(digit);
+        }
+
+        result
+    }
+
+    /// Returns the digits of the magnitude in the provided radix, without sign, with letter
+    /// digits in uppercase or lowercase.
+    pub(crate) fn to_digit_string(&self, radix: u8, uppercase: bool) -> String {
+        let letters = if uppercase { b'A' } else { b'a' };
+
+        self.to_digits(radix)
+            .into_iter()
+            .map(|digit| {
+                if digit < 10 {
+                    (b'0' + digit) as char
+                } else {
+                    (letters + (digit - 10)) as char
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the number in scientific notation, like `1.2345678e+1000000`, with `digits`
+    /// significant digits (at least one) rounded with `mode`.
+    ///
+    /// Only the leading digits are computed, so this stays fast for huge values.
+    pub fn to_scientific(&self, digits: usize, mode: RoundingMode) -> String {
+        let digits = digits.max(1);
+        let count = self.digit_count(10);
+
+        let (mantissa, exponent) = if count <= digits {
+            (self.abs() * BigInt::power_of(10, digits - count), count - 1)
+        } else {
+            let (mut mantissa, fraction) = self.scale_down(10, count - digits);
+            let odd = mantissa.magnitude().first().is_some_and(|low| low & 1 == 1);
+
+            if fraction.rounds_up(mode, self.is_negative(), odd) {
+                mantissa += 1u8;
+            }
+
+            // Rounding 99…9 up adds a digit.
+            if mantissa.digit_count(10) > digits {
+                (mantissa / 10u8, count)
+            } else {
+                (mantissa, count - 1)
+            }
+        };
+
+        let sign = if self.is_negative() { "-" } else { "" };
+        let mantissa = format!("{:0>digits$}", mantissa.to_digit_string(10, false));
+        let (first, rest) = mantissa.split_at(1);
+
+        if rest.is_empty() {
+            format!("{}{}e+{}", sign, first, exponent)
+        } else {
+            format!("{}{}.{}e+{}", sign, first, rest, exponent)
+        }
+    }
+
+    /// Returns the number with only its first and last `digits` decimal digits, like
+    /// `12345…67890 (1000001 digits)`. Numbers too short to elide anything are written in full.
+    ///
+    /// See [`BigInt::leading_digits`] and [`BigInt::trailing_digits`]: this stays fast for huge
+    /// values.
+    pub fn to_elided(&self, digits: usize) -> String {
+        let digits = digits.max(1);
+        let count = self.decimal_digit_count();
+
+        if count <= 2 * digits {
+            return self.to_string(Base::Decimal);
+        }
+
+        format!(
+            "{}{}…{} ({} digits)",
+            if self.is_negative() { "-" } else { "" },
+            self.leading_digits_of(digits, 10, count),
+            self.trailing_digits(digits, Base::Decimal),
+            count
+        )
+    }
+}
+
+// `pad_integral` applies the width, fill, alignment, `+`, `#` and `0` flags the same way as for
+// the primitive integers, the sign going before the prefix and the zero padding.
+macro_rules! impl_format {
+    ($($format:ident => $radix:expr, $prefix:expr, $uppercase:expr);*) => {
+        $(
+            impl std::fmt::$format for BigInt {
+                fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    let digits = self.to_digit_string($radix, $uppercase);
+
+                    formatter.pad_integral(!self.is_negative(), $prefix, &digits)
+                }
+            }
+        )*
+    };
+}
+
+impl_format!(
+    Display => 10, "", false;
+    LowerHex => 16, "0x", false;
+    UpperHex => 16, "0x", true;
+    Octal => 8, "0o", false;
+    Binary => 2, "0b", false
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{Base, BigInt, FormatOptions, LiteralOptions, RoundingMode};
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn should_pad_and_align() {
+            // Given
+            let value = BigInt::from(-42i8);
+
+            // When / Then
+            assert_eq!(format!("{}", value), "-42");
+            assert_eq!(format!("{:6}", value), "   -42");
+            assert_eq!(format!("{:<6}|", value), "-42   |");
+            assert_eq!(format!("{:*^7}", value), "**-42**");
+            assert_eq!(format!("{:06}", value), "-00042");
+        }
+
+        #[test]
+        fn should_write_an_explicit_plus_sign() {
+            // When / Then
+            assert_eq!(format!("{:+}", BigInt::from(7u8)), "+7");
+            assert_eq!(format!("{:+05}", BigInt::from(0u8)), "+0000");
+            assert_eq!(format!("{:+}", BigInt::from(-7i8)), "-7");
+        }
+    }
+
+    mod radix {
+        use super::*;
+
+        #[test]
+        fn should_format_in_hexadecimal() {
+            // Given
+            let value = BigInt::from(0xDEAD_BEEFu32);
+
+            // When / Then
+            assert_eq!(format!("{:x}", value), "deadbeef");
+            assert_eq!(format!("{:X}", value), "DEADBEEF");
+            assert_eq!(format!("{:#018x}", value), "0x00000000deadbeef");
+            assert_eq!(format!("{:#X}", value), "0xDEADBEEF");
+        }
+
+        #[test]
+        fn should_format_in_binary_and_octal() {
+            // Given
+            let value = BigInt::from(10u8);
+
+            // When / Then
+            assert_eq!(format!("{:b}", value), "1010");
+            assert_eq!(format!("{:#b}", value), "0b1010");
+            assert_eq!(format!("{:o}", value), "12");
+            assert_eq!(format!("{:#o}", value), "0o12");
+        }
+
+        #[test]
+        fn should_place_the_sign_before_the_prefix() {
+            // Given
+            let value = -BigInt::from(255u8);
+
+            // When / Then
+            assert_eq!(format!("{:#x}", value), "-0xff");
+            assert_eq!(format!("{:#010x}", value), "-0x00000ff");
+            assert_eq!(format!("{:>+8x}", BigInt::from(255u8)), "     +ff");
+        }
+    }
+
+    mod options {
+        use super::*;
+
+        #[test]
+        fn should_match_to_string_by_default() {
+            // Given
+            let value = BigInt::from(-0xBEEFi32);
+
+            // When
+            let result = value.to_string_with_options(Base::Hexadecimal, FormatOptions::new());
+
+            // Then
+            assert_eq!(result, value.to_string(Base::Hexadecimal));
+        }
+
+        #[test]
+        fn should_group_digits() {
+            // Given
+            let thousands = FormatOptions::new().group_separator(',');
+            let words = FormatOptions::new()
+                .group_separator('_')
+                .group_size(4)
+                .prefix(true);
+
+            // When / Then
+            assert_eq!(
+                BigInt::from(1234567u32).to_string_with_options(Base::Decimal, thousands),
+                "1,234,567"
+            );
+            assert_eq!(
+                BigInt::from(-123i8).to_string_with_options(Base::Decimal, thousands),
+                "-123"
+            );
+            assert_eq!(
+                BigInt::from(0xDEAD_BEEFu32).to_string_with_options(Base::Hexadecimal, words),
+                "0xDEAD_BEEF"
+            );
+        }
+
+        #[test]
+        fn should_pad_case_and_sign() {
+            // Given
+            let options = FormatOptions::new()
+                .uppercase(false)
+                .min_digits(6)
+                .explicit_plus(true)
+                .group_separator(' ')
+                .group_size(2);
+
+            // When / Then
+            assert_eq!(
+                BigInt::from(0xABCu16).to_string_with_options(Base::Hexadecimal, options),
+                "+00 0a bc"
+            );
+            assert_eq!(
+                BigInt::from(0u8).to_string_with_options(Base::Decimal, options),
+                "+00 00 00"
+            );
+        }
+
+        #[test]
+        fn should_be_read_back_as_a_literal() {
+            // Given
+            let value = -(BigInt::from(u128::MAX) << 3);
+            let options = FormatOptions::new().group_separator('_').prefix(true);
+
+            // When
+            let result = value.to_string_with_options(Base::Octal, options);
+
+            // Then
+            assert_eq!(
+                BigInt::from_literal(&result, LiteralOptions::new()),
+                Ok(value)
+            );
+        }
+    }
+
+    mod scientific {
+        use super::*;
+
+        #[test]
+        fn should_round_the_significant_digits() {
+            // Given
+            let value = BigInt::from(123456789u32);
+
+            // When / Then
+            assert_eq!(value.to_scientific(4, RoundingMode::TowardZero), "1.234e+8");
+            assert_eq!(value.to_scientific(4, RoundingMode::HalfEven), "1.235e+8");
+            assert_eq!(value.to_scientific(1, RoundingMode::Ceiling), "2e+8");
+            assert_eq!(
+                value.to_scientific(12, RoundingMode::Floor),
+                "1.23456789000e+8"
+            );
+        }
+
+        #[test]
+        fn should_carry_into_the_exponent() {
+            // Given
+            let value = BigInt::from(-99960i32);
+
+            // When / Then
+            assert_eq!(value.to_scientific(3, RoundingMode::HalfEven), "-1.00e+5");
+            assert_eq!(value.to_scientific(3, RoundingMode::Ceiling), "-9.99e+4");
+            assert_eq!(value.to_scientific(3, RoundingMode::Floor), "-1.00e+5");
+        }
+
+        #[test]
+        fn should_write_zero() {
+            // When / Then
+            assert_eq!(
+                BigInt::from(0u8).to_scientific(3, RoundingMode::HalfEven),
+                "0.00e+0"
+            );
+        }
+
+        #[test]
+        fn should_format_huge_values() {
+            // Given
+            let value = BigInt::power_of(3, 20000);
+
+            // When
+            let result = value.to_scientific(8, RoundingMode::HalfEven);
+
+            // Then
+            assert_eq!(result, "2.6613034e+9542");
+        }
+    }
+
+    mod elided {
+        use super::*;
+
+        #[test]
+        fn should_keep_the_ends_of_huge_values() {
+            // Given
+            let value = -BigInt::power_of(3, 20000);
+
+            // When
+            let result = value.to_elided(5);
+
+            // Then
+            assert_eq!(result, "-26613…00001 (9543 digits)");
+        }
+
+        #[test]
+        fn should_write_short_values_in_full() {
+            // When / Then
+            assert_eq!(BigInt::from(1234567890u32).to_elided(5), "1234567890");
+            assert_eq!(
+                BigInt::from(12345678901u64).to_elided(5),
+                "12345…78901 (11 digits)"
+            );
+        }
+    }
+}
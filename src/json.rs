@@ -0,0 +1,254 @@
+//! JSON numbers of any length, written bare rather than as strings.
+
+use crate::parse::{ParseBigIntError, ParseErrorKind};
+use crate::{Base, BigInt};
+
+/// Options for [`BigInt::from_json`].
+///
+/// By default only integer tokens such as `-123` are accepted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonOptions {
+    /// Largest power of ten the digits may be scaled by, if fractions and exponents are
+    /// accepted.
+    max_exponent: Option<u32>,
+}
+
+impl JsonOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept a fraction and an exponent, as in `1e30` or `2.5E+3`, when the value is an
+    /// integer. The digits may be scaled by at most `10^max`, which bounds the size of the
+    /// result.
+    pub fn exponent(mut self, max: u32) -> Self {
+        self.max_exponent = Some(max);
+        self
+    }
+}
+
+/// Returns the end of the run of decimal digits at `start`, which must not be empty.
+fn scan_digits(bytes: &[u8], start: usize) -> Result<usize, ParseBigIntError> {
+    let length = bytes[start..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+
+    if length == 0 {
+        return Err(unexpected(bytes, start));
+    }
+
+    Ok(start + length)
+}
+
+/// Returns the error for the unexpected byte at `position`, or for the end of the input.
+fn unexpected(bytes: &[u8], position: usize) -> ParseBigIntError {
+    let kind = match bytes.get(position) {
+        None => ParseErrorKind::Empty,
+        Some(b'-' | b'+') => ParseErrorKind::MisplacedSign,
+        Some(_) => ParseErrorKind::InvalidDigit,
+    };
+
+    ParseBigIntError::new(kind, position)
+}
+
+impl BigInt {
+    /// Returns the value as a JSON number: decimal digits and a leading `-` if negative.
+    pub fn to_json(&self) -> String {
+        self.to_string(Base::Decimal)
+    }
+
+    /// Create a new `BigInt` from a JSON number token, without the whitespace around it. See
+    /// [`JsonOptions`] for the handling of fractions and exponents.
+    pub fn from_json(data: &str, options: JsonOptions) -> Result<Self, ParseBigIntError> {
+        let bytes = data.as_bytes();
+
+        let negative = bytes.first() == Some(&b'-');
+        let start = negative as usize;
+
+        // The integer part is a single zero or does not start with a zero.
+        let end = scan_digits(bytes, start)?;
+
+        if bytes[start] == b'0' && end > start + 1 {
+            return Err(ParseBigIntError::new(
+                ParseErrorKind::InvalidDigit,
+                start + 1,
+            ));
+        }
+
+        let mut digits: Vec<u8> = bytes[start..end].iter().map(|byte| byte - b'0').collect();
+        let mut exponent = 0i64;
+        let mut position = end;
+
+        if options.max_exponent.is_some() {
+            if bytes.get(position) == Some(&b'.') {
+                let end = scan_digits(bytes, position + 1)?;
+
+                digits.extend(bytes[position + 1..end].iter().map(|byte| byte - b'0'));
+                exponent -= (end - position - 1) as i64;
+                position = end;
+            }
+
+            if matches!(bytes.get(position), Some(b'e' | b'E')) {
+                let negative_exponent = bytes.get(position + 1) == Some(&b'-');
+
+                if matches!(bytes.get(position + 1), Some(b'-' | b'+')) {
+                    position += 1;
+                }
+
+                let exponent_end = scan_digits(bytes, position + 1)?;
+                let value = bytes[position + 1..exponent_end]
+                    .iter()
+                    .fold(0i64, |value, byte| {
+                        value
+                            .saturating_mul(10)
+                            .saturating_add((byte - b'0') as i64)
+                    });
+
+                exponent = if negative_exponent {
+                    exponent.saturating_sub(value)
+                } else {
+                    exponent.saturating_add(value)
+                };
+                position = exponent_end;
+            }
+        }
+
+        if position < bytes.len() {
+            return Err(unexpected(bytes, position));
+        }
+
+        let Some(last) = digits.iter().rposition(|&digit| digit != 0) else {
+            return Ok(BigInt::from(0u8));
+        };
+
+        // A negative exponent must only drop trailing zeros.
+        if exponent < 0 {
+            let trailing_zeros = (digits.len() - 1 - last) as i64;
+
+            if trailing_zeros < exponent.saturating_neg() {
+                return Err(ParseBigIntError::new(ParseErrorKind::NotAnInteger, end));
+            }
+
+            digits.truncate((digits.len() as i64 + exponent) as usize);
+            exponent = 0;
+        }
+
+        if exponent > options.max_exponent.unwrap_or(0) as i64 {
+            return Err(ParseBigIntError::new(
+                ParseErrorKind::ExponentOutOfRange,
+                end,
+            ));
+        }
+
+        let value = BigInt::from_digits(&digits, 10, negative);
+
+        if exponent == 0 {
+            Ok(value)
+        } else {
+            Ok(value * BigInt::power_of(10, exponent as usize))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigInt, JsonOptions, ParseErrorKind};
+
+    mod write {
+        use super::*;
+
+        #[test]
+        fn should_write_bare_digits() {
+            // Given
+            let value: BigInt = "-123456789012345678901234567890".parse().unwrap();
+
+            // When / Then
+            assert_eq!(value.to_json(), "-123456789012345678901234567890");
+            assert_eq!(BigInt::from(0u8).to_json(), "0");
+        }
+    }
+
+    mod read {
+        use super::*;
+
+        /// Returns the kind and position of the error parsing `data`.
+        fn error(data: &str, options: JsonOptions) -> (ParseErrorKind, usize) {
+            let error = BigInt::from_json(data, options).unwrap_err();
+
+            (error.kind(), error.position())
+        }
+
+        #[test]
+        fn should_parse_integers_of_any_length() {
+            // Given
+            let data = "123456789012345678901234567890";
+
+            // When
+            let value = BigInt::from_json(data, JsonOptions::new());
+
+            // Then
+            assert_eq!(value.unwrap().to_json(), data);
+            assert_eq!(
+                BigInt::from_json("-0", JsonOptions::new()),
+                Ok(BigInt::from(0u8))
+            );
+        }
+
+        #[test]
+        fn should_follow_the_json_grammar() {
+            // Given
+            let options = JsonOptions::new();
+
+            // When / Then
+            assert_eq!(error("", options), (ParseErrorKind::Empty, 0));
+            assert_eq!(error("-", options), (ParseErrorKind::Empty, 1));
+            assert_eq!(error("+1", options), (ParseErrorKind::MisplacedSign, 0));
+            assert_eq!(error("--1", options), (ParseErrorKind::MisplacedSign, 1));
+            assert_eq!(error("012", options), (ParseErrorKind::InvalidDigit, 1));
+            assert_eq!(error(" 1", options), (ParseErrorKind::InvalidDigit, 0));
+            assert_eq!(error("1e3", options), (ParseErrorKind::InvalidDigit, 1));
+            assert_eq!(error("1.0", options), (ParseErrorKind::InvalidDigit, 1));
+        }
+
+        #[test]
+        fn should_scale_by_the_exponent_when_asked() {
+            // Given
+            let options = JsonOptions::new().exponent(30);
+            let parse = |data| BigInt::from_json(data, options).unwrap();
+
+            // When / Then
+            assert_eq!(parse("1e30"), BigInt::from(10u128.pow(30)));
+            assert_eq!(parse("-2.5E+3"), BigInt::from(-2500i16));
+            assert_eq!(parse("12000e-3"), BigInt::from(12u8));
+            assert_eq!(parse("1.000"), BigInt::from(1u8));
+            assert_eq!(parse("0.0e-999999999999999999999"), BigInt::from(0u8));
+        }
+
+        #[test]
+        fn should_reject_fractional_values_and_large_exponents() {
+            // Given
+            let options = JsonOptions::new().exponent(30);
+
+            // When / Then
+            assert_eq!(error("1.5", options), (ParseErrorKind::NotAnInteger, 1));
+            assert_eq!(
+                error("1e-99999999999999999999", options),
+                (ParseErrorKind::NotAnInteger, 1)
+            );
+            assert_eq!(error("15e-2", options), (ParseErrorKind::NotAnInteger, 2));
+            assert_eq!(
+                error("1e31", options),
+                (ParseErrorKind::ExponentOutOfRange, 1)
+            );
+            assert_eq!(
+                error("1e99999999999999999999", options),
+                (ParseErrorKind::ExponentOutOfRange, 1)
+            );
+            assert_eq!(error("1.", options), (ParseErrorKind::Empty, 2));
+            assert_eq!(error("1e+", options), (ParseErrorKind::Empty, 3));
+            assert_eq!(error("1e3.0", options), (ParseErrorKind::InvalidDigit, 3));
+        }
+    }
+}
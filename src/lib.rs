@@ -1,22 +1,98 @@
+mod alphabet;
+mod bytes;
+mod cbor;
+mod convert;
+mod decode;
+mod der;
+mod digits;
+mod float;
+mod format;
+mod json;
+mod magnitude;
+mod mpint;
+mod ops;
+mod parse;
+mod primitive;
+mod radix;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod snapshot;
+mod stream;
+#[cfg(test)]
+mod testing;
+mod varint;
+mod words;
+
+pub use alphabet::{Alphabet, AlphabetError};
+pub use bytes::ByteLengthError;
+pub use convert::TryFromBigIntError;
+pub use decode::{DecodeError, DecodeErrorKind};
+pub use float::{FromFloatError, RoundingMode};
+pub use format::FormatOptions;
+pub use json::JsonOptions;
+pub use parse::{LiteralOptions, ParseBigIntError, ParseErrorKind};
+pub use snapshot::SnapshotError;
+pub use varint::TruncatedVarintError;
+pub use words::{Order, WordLayout, WordLayoutError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Base {
     Binary,
     Octal,
     Decimal,
     Hexadecimal,
+    /// Any other radix from 3 to 36. The digits above 9 are the letters `a` to `z`.
+    Radix(Radix),
+}
+
+/// A radix from 3 to 36 without a named `Base` variant, built by [`Base::from_radix`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Radix(u8);
+
+impl Radix {
+    /// Returns the value of the radix.
+    pub fn get(self) -> u8 {
+        self.0
+    }
 }
 
 impl Base {
+    /// Returns the base for a radix, or `None` if the radix is not between 2 and 36. The radices
+    /// 2, 8, 10 and 16 give the named variants.
+    pub fn from_radix(radix: u8) -> Option<Base> {
+        match radix {
+            2 => Some(Base::Binary),
+            8 => Some(Base::Octal),
+            10 => Some(Base::Decimal),
+            16 => Some(Base::Hexadecimal),
+            3..=36 => Some(Base::Radix(Radix(radix))),
+            _ => None,
+        }
+    }
+
     /// Returns the radix of the base.
-    fn to_radix(&self) -> u8 {
+    fn to_radix(self) -> u8 {
         match self {
             Base::Binary => 2,
             Base::Octal => 8,
             Base::Decimal => 10,
             Base::Hexadecimal => 16,
+            Base::Radix(radix) => radix.get(),
         }
     }
 }
 
+/// Sign of a big integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Sign {
+    /// The value is negative.
+    Minus,
+    /// The value is zero.
+    NoSign,
+    /// The value is positive.
+    Plus,
+}
+
 /// A big integer data structure.
 pub struct BigInt {
     /// The underlying data structure used to store the big integer.
@@ -34,10 +110,40 @@ impl BigInt {
         BigInt { data, sign }
     }
 
+    /// Create a new `BigInt` from a sign and a little-endian magnitude.
+    /// `Sign::NoSign` always gives zero, whatever the magnitude.
+    pub fn new(sign: Sign, data: Vec<u8>) -> Self {
+        match sign {
+            Sign::Minus => BigInt::from_magnitude(data, true),
+            Sign::NoSign => BigInt::from_magnitude(vec![], false),
+            Sign::Plus => BigInt::from_magnitude(data, false),
+        }
+    }
+
+    /// Create a new `BigInt` from a magnitude, dropping the high zero bytes and clearing the sign
+    /// of zero so that every operation hands back a canonical value.
+    fn from_magnitude(mut data: Vec<u8>, sign: bool) -> Self {
+        magnitude::trim(&mut data);
+
+        let sign = sign && !data.is_empty();
+
+        BigInt { data, sign }
+    }
+
+    /// Drop the high zero bytes and clear the sign of zero.
+    fn normalize(&mut self) {
+        magnitude::trim(&mut self.data);
+
+        if self.data.is_empty() {
+            self.sign = false;
+        }
+    }
+
     /// Create a new `BigInt` from a string, using the provided base.
     pub fn from_string(data: String, base: Base) -> Self {
         let radix = base.to_radix();
-        let mut result = BigInt::from_bytes(vec![], false);
+        let mut digits = Vec::with_capacity(data.len());
+        let mut negative = false;
 
         for character in data.chars() {
             let value = match character {
@@ -45,8 +151,8 @@ impl BigInt {
                 'a'..='z' => character as u8 - b'a' + 10,
                 'A'..='Z' => character as u8 - b'A' + 10,
                 '-' => {
-                    if result.is_zero() {
-                        result.sign = true;
+                    if digits.is_empty() {
+                        negative = true;
                     }
 
                     continue;
@@ -58,47 +164,67 @@ impl BigInt {
                 panic!("Invalid character {} for base {}", character, radix);
             }
 
-            result = result.multiply_by_small(radix) + BigInt::from_bytes(vec![value], false);
+            // Leading zeros are dropped, so no digits means the value is still zero.
+            if value != 0 || !digits.is_empty() {
+                digits.push(value);
+            }
         }
 
-        result
+        BigInt::from_digits(&digits, radix, negative)
     }
 
     /// Returns the number encoded as a string in the provided base.
     pub fn to_string(&self, base: Base) -> String {
-        if self.is_zero() {
-            return "0".to_string();
+        let digits = self.to_digit_string(base.to_radix(), true);
+
+        if self.is_negative() {
+            format!("-{}", digits)
+        } else {
+            digits
         }
+    }
 
-        let mut digits = Vec::new();
+    /// Returns the digit values of the magnitude in the provided radix, most significant first.
+    /// Zero is a single `0` digit.
+    pub(crate) fn to_digits(&self, radix: u8) -> Vec<u8> {
+        if radix.is_power_of_two() || self.data.len() > radix::SMALL_LENGTH {
+            return radix::to_digits(&self.data, radix);
+        }
 
-        let radix = base.to_radix();
-        let mut temp = self.clone();
+        let radix = radix as u64;
 
-        while !temp.is_zero() {
-            let (quotient, remainder) = temp.divide_by_small(radix);
+        // Peel as many digits as fit in a word with each division of the big number.
+        let (chunk_radix, chunk_length) = magnitude::largest_power(radix);
 
-            digits.push(remainder);
+        let mut digits = Vec::new();
+        let mut temp = self.abs();
+
+        while !temp.is_zero() {
+            let (quotient, mut remainder) = temp.divide_by_small(chunk_radix);
 
             temp = quotient;
+
+            for _ in 0..chunk_length {
+                if temp.is_zero() && remainder == 0 {
+                    break;
+                }
+
+                digits.push((remainder % radix) as u8);
+                remainder /= radix;
+            }
+        }
+
+        if digits.is_empty() {
+            digits.push(0);
         }
 
         digits.reverse(); // The digits were obtained in reverse order.
 
         digits
-            .into_iter()
-            .map(|digit| {
-                if digit < 10 {
-                    (b'0' + digit) as char
-                } else {
-                    (b'A' + (digit - 10)) as char
-                }
-            })
-            .collect()
     }
 
     /// Returns `true` if BigInt is zero.
-    fn is_zero(&self) -> bool {
+    pub fn is_zero(&self) -> bool {
         if self.data.is_empty() {
             return true;
         }
@@ -107,54 +233,66 @@ impl BigInt {
             return true;
         }
 
-        return false;
+        false
     }
 
-    /// Multiply BigInt by a small number (u8).
-    fn multiply_by_small(&self, multiplier: u8) -> BigInt {
+    /// Returns the sign of the BigInt. Zero has no sign.
+    pub fn sign(&self) -> Sign {
         if self.is_zero() {
-            return BigInt::from_bytes(vec![], false);
+            Sign::NoSign
+        } else if self.sign {
+            Sign::Minus
+        } else {
+            Sign::Plus
         }
+    }
 
-        let mut result = Vec::with_capacity(self.data.len() + 1);
-        let mut carry: u16 = 0;
+    /// Returns `true` if BigInt is greater than zero.
+    pub fn is_positive(&self) -> bool {
+        self.sign() == Sign::Plus
+    }
 
-        for &digit in &self.data {
-            let prod = digit as u16 * multiplier as u16 + carry;
-            result.push((prod % 256) as u8);
-            carry = prod / 256;
-        }
+    /// Returns `true` if BigInt is lower than zero.
+    pub fn is_negative(&self) -> bool {
+        self.sign() == Sign::Minus
+    }
+
+    /// Returns the absolute value of the BigInt.
+    pub fn abs(&self) -> BigInt {
+        BigInt::from_magnitude(self.magnitude().to_vec(), false)
+    }
 
-        while carry > 0 {
-            result.push((carry % 256) as u8);
-            carry /= 256;
+    /// Returns `-1`, `0` or `1` according to the sign of the BigInt.
+    pub fn signum(&self) -> BigInt {
+        match self.sign() {
+            Sign::Minus => BigInt::from(-1i8),
+            Sign::NoSign => BigInt::from(0u8),
+            Sign::Plus => BigInt::from(1u8),
         }
+    }
 
-        BigInt::from_bytes(result, false)
+    /// Returns the magnitude as little-endian bytes, without high zero bytes.
+    pub fn magnitude(&self) -> &[u8] {
+        magnitude::significant(&self.data)
     }
 
-    /// Divide BigInt by a small divisor (u8) and return the pair (quotient, remainder).
-    fn divide_by_small(&self, divisor: u8) -> (BigInt, u8) {
-        if self.is_zero() {
-            return (BigInt::from_bytes(vec![], false), 0);
-        }
+    /// Multiply BigInt by a small number (u64).
+    fn multiply_by_small(&self, multiplier: u64) -> BigInt {
+        let mut data = self.data.clone();
 
-        let mut remainder: u16 = 0;
-        let mut quotient = vec![0u8; self.data.len()];
+        magnitude::mul_word_assign(&mut data, multiplier);
 
-        // Since the representation is little-endian, we iterate from the most significant byte.
-        for i in (0..self.data.len()).rev() {
-            remainder = remainder * 256 + self.data[i] as u16;
-            quotient[i] = (remainder / divisor as u16) as u8;
-            remainder %= divisor as u16;
-        }
+        BigInt::from_magnitude(data, self.sign)
+    }
 
-        // Remove leading zeros (in the little-endian representation, they are the last elements).
-        while quotient.len() > 1 && *quotient.last().unwrap() == 0 {
-            quotient.pop();
-        }
+    /// Divide BigInt by a small divisor (u64) and return the pair (quotient, remainder).
+    /// The remainder is the one of the magnitude.
+    fn divide_by_small(&self, divisor: u64) -> (BigInt, u64) {
+        let mut quotient = self.data.clone();
 
-        (BigInt::from_bytes(quotient, false), remainder as u8)
+        let remainder = magnitude::div_word_assign(&mut quotient, divisor);
+
+        (BigInt::from_magnitude(quotient, self.sign), remainder)
     }
 }
 
@@ -185,12 +323,6 @@ impl std::fmt::Debug for BigInt {
     }
 }
 
-impl std::fmt::Display for BigInt {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        self.to_string(Base::Decimal).fmt(formatter)
-    }
-}
-
 impl std::cmp::PartialEq for BigInt {
     fn eq(&self, other: &Self) -> bool {
         if self.is_zero() && other.is_zero() {
@@ -201,41 +333,7 @@ impl std::cmp::PartialEq for BigInt {
             return false;
         }
 
-        if self.data != other.data {
-            return false;
-        }
-
-        return true;
-    }
-}
-
-impl std::ops::Add for BigInt {
-    type Output = Self;
-
-    fn add(self, right: Self) -> Self {
-        let left_data = &self.data;
-        let right_data = &right.data;
-
-        let longest_number_length = left_data.len().max(right_data.len());
-
-        let mut result = Vec::with_capacity(longest_number_length);
-        let mut carry = 0u8;
-
-        for index in 0..longest_number_length {
-            let left = *left_data.get(index).unwrap_or(&0) as u16;
-            let right = *right_data.get(index).unwrap_or(&0) as u16;
-
-            let total = left + right + carry as u16;
-
-            result.push((total % 256) as u8);
-            carry = (total / 256) as u8;
-        }
-
-        if carry > 0 {
-            result.push(carry);
-        }
-
-        BigInt::from_bytes(result, false)
+        magnitude::compare(&self.data, &other.data) == std::cmp::Ordering::Equal
     }
 }
 
@@ -361,6 +459,61 @@ mod display {
     }
 }
 
+#[cfg(test)]
+mod base {
+    use super::*;
+
+    mod from_radix {
+        use super::*;
+
+        #[test]
+        fn should_return_the_named_bases() {
+            // When / Then
+            assert_eq!(Base::from_radix(2), Some(Base::Binary));
+            assert_eq!(Base::from_radix(10), Some(Base::Decimal));
+            assert_eq!(Base::from_radix(16), Some(Base::Hexadecimal));
+            assert_eq!(Base::from_radix(36), Some(Base::Radix(Radix(36))));
+        }
+
+        #[test]
+        fn should_reject_radixes_out_of_range() {
+            // When / Then
+            assert_eq!(Base::from_radix(0), None);
+            assert_eq!(Base::from_radix(1), None);
+            assert_eq!(Base::from_radix(37), None);
+        }
+    }
+
+    mod radix {
+        use super::*;
+
+        #[test]
+        fn should_round_trip_base_36() {
+            // Given
+            let data = "ZZ0PQRSTUVWXYZ".to_string();
+            let base = Base::from_radix(36).unwrap();
+
+            // When
+            let bigint = BigInt::from_string(data.clone(), base);
+
+            // Then
+            assert_eq!(bigint.to_string(base), data);
+        }
+
+        #[test]
+        fn should_display_bigint_in_base_3() {
+            // Given
+            let bigint = BigInt::from_bytes(vec![0xFF], false);
+
+            // When
+            let result = bigint.to_string(Base::from_radix(3).unwrap());
+
+            // Then
+            assert_eq!(result, "100110");
+        }
+    }
+}
+
 #[cfg(test)]
 mod comparators {
     use super::*;
@@ -379,7 +532,7 @@ mod comparators {
             let result = a == b;
 
             // Then
-            assert_eq!(result, true);
+            assert!(result);
         }
 
         #[test]
@@ -392,7 +545,7 @@ mod comparators {
             let result = a == b;
 
             // Then
-            assert_eq!(result, false);
+            assert!(!result);
         }
 
         #[test]
@@ -409,7 +562,7 @@ mod comparators {
             let result = a == b;
 
             // Then
-            assert_eq!(result, false);
+            assert!(!result);
         }
 
         #[test]
@@ -426,7 +579,7 @@ mod comparators {
             let result = a == b;
 
             // Then
-            assert_eq!(result, true);
+            assert!(result);
         }
 
         #[test]
@@ -439,7 +592,7 @@ mod comparators {
             let result = a == b;
 
             // Then
-            assert_eq!(result, true);
+            assert!(result);
         }
     }
 }
@@ -514,6 +667,79 @@ mod operators {
     }
 }
 
+#[cfg(test)]
+mod signs {
+    use super::*;
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn should_create_a_negative_bigint() {
+            // When
+            let bigint = BigInt::new(Sign::Minus, vec![0x05, 0x00]);
+
+            // Then
+            assert_eq!(bigint, BigInt::from(-5i8));
+            assert_eq!(bigint.magnitude(), &[0x05]);
+        }
+
+        #[test]
+        fn should_create_zero_without_sign() {
+            // When
+            let bigint = BigInt::new(Sign::NoSign, vec![0x05]);
+
+            // Then
+            assert!(bigint.is_zero());
+        }
+    }
+
+    mod sign {
+        use super::*;
+
+        #[test]
+        fn should_return_the_sign() {
+            // Given
+            let negative = BigInt::from_bytes(vec![0x01], true);
+            let negative_zero = BigInt::from_bytes(vec![0x00], true);
+            let positive = BigInt::from_bytes(vec![0x01], false);
+
+            // When / Then
+            assert_eq!(negative.sign(), Sign::Minus);
+            assert_eq!(negative_zero.sign(), Sign::NoSign);
+            assert_eq!(positive.sign(), Sign::Plus);
+        }
+
+        #[test]
+        fn should_tell_positive_and_negative_values_apart() {
+            // Given
+            let negative = BigInt::from(-3i8);
+            let zero = BigInt::from(0u8);
+
+            // When / Then
+            assert!(negative.is_negative());
+            assert!(!negative.is_positive());
+            assert!(!zero.is_negative());
+            assert!(!zero.is_positive());
+        }
+    }
+
+    mod abs {
+        use super::*;
+
+        #[test]
+        fn should_return_the_absolute_value_and_the_signum() {
+            // Given
+            let bigint = BigInt::from(-300i16);
+
+            // When / Then
+            assert_eq!(bigint.abs(), BigInt::from(300u16));
+            assert_eq!(bigint.signum(), BigInt::from(-1i8));
+            assert_eq!(BigInt::from(0u8).signum(), BigInt::from(0u8));
+        }
+    }
+}
+
 #[cfg(test)]
 mod utils {
     use super::*;
@@ -531,7 +757,7 @@ mod utils {
             let result = bigint.is_zero();
 
             // Then
-            assert_eq!(result, true);
+            assert!(result);
         }
 
         #[test]
@@ -543,7 +769,7 @@ mod utils {
             let result = bigint.is_zero();
 
             // Then
-            assert_eq!(result, true);
+            assert!(result);
         }
 
         #[test]
@@ -555,7 +781,7 @@ mod utils {
             let result = bigint.is_zero();
 
             // Then
-            assert_eq!(result, false);
+            assert!(!result);
         }
     }
 
@@ -0,0 +1,1353 @@
+//! Unsigned arithmetic over little-endian byte slices.
+//!
+//! Every function here works on magnitudes only; the sign is handled by the callers. Results
+//! never carry high zero bytes. Multiplication and division pack the bytes into 64-bit words
+//! so that the inner loops do not run once per byte.
+
+use std::cmp::Ordering;
+
+/// Below this many words a schoolbook multiplication beats Karatsuba.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Below this many words Karatsuba beats Toom-3.
+const TOOM_3_THRESHOLD: usize = 192;
+
+/// Below this many words Toom-3 beats the number-theoretic transform.
+const NTT_THRESHOLD: usize = 3000;
+
+/// The prime `2^64 - 2^32 + 1` of the number-theoretic transform, which has roots of unity of
+/// every power of two order up to `2^32`.
+const NTT_PRIME: u64 = 0xFFFF_FFFF_0000_0001;
+
+/// A generator of the multiplicative group modulo `NTT_PRIME`.
+const NTT_GENERATOR: u64 = 7;
+
+/// Below this many divisor words Knuth's division beats Burnikel and Ziegler's.
+const BURNIKEL_ZIEGLER_THRESHOLD: usize = 64;
+
+/// Remove the high zero bytes (the last elements in the little-endian representation).
+pub(crate) fn trim(data: &mut Vec<u8>) {
+    while data.last() == Some(&0) {
+        data.pop();
+    }
+}
+
+/// Returns the slice without its high zero bytes.
+pub(crate) fn significant(data: &[u8]) -> &[u8] {
+    let length = data.len() - data.iter().rev().take_while(|&&byte| byte == 0).count();
+
+    &data[..length]
+}
+
+/// Compare two magnitudes.
+pub(crate) fn compare(left: &[u8], right: &[u8]) -> Ordering {
+    let left = significant(left);
+    let right = significant(right);
+
+    left.len()
+        .cmp(&right.len())
+        .then_with(|| left.iter().rev().cmp(right.iter().rev()))
+}
+
+/// Add `right` to `left` in place.
+pub(crate) fn add_assign(left: &mut Vec<u8>, right: &[u8]) {
+    if left.len() < right.len() {
+        left.resize(right.len(), 0);
+    }
+
+    let mut carry = 0u16;
+
+    for (index, byte) in left.iter_mut().enumerate() {
+        let right = match right.get(index) {
+            Some(&byte) => byte,
+            None if carry == 0 => break,
+            None => 0,
+        };
+
+        let total = *byte as u16 + right as u16 + carry;
+
+        *byte = total as u8;
+        carry = total >> 8;
+    }
+
+    if carry > 0 {
+        left.push(carry as u8);
+    }
+
+    trim(left);
+}
+
+/// Subtract `right` from `left` in place. `left` must not be smaller than `right`.
+pub(crate) fn sub_assign(left: &mut Vec<u8>, right: &[u8]) {
+    let mut borrow = 0i16;
+
+    for (index, byte) in left.iter_mut().enumerate() {
+        let right = match right.get(index) {
+            Some(&byte) => byte,
+            None if borrow == 0 => break,
+            None => 0,
+        };
+
+        let mut difference = *byte as i16 - right as i16 - borrow;
+
+        borrow = 0;
+
+        if difference < 0 {
+            difference += 256;
+            borrow = 1;
+        }
+
+        *byte = difference as u8;
+    }
+
+    debug_assert_eq!(borrow, 0, "magnitude subtraction underflow");
+
+    trim(left);
+}
+
+/// Replace `right` by `left - right` in place. `left` must not be smaller than `right`.
+pub(crate) fn reverse_sub_assign(left: &[u8], right: &mut Vec<u8>) {
+    if right.len() < left.len() {
+        right.resize(left.len(), 0);
+    }
+
+    let mut borrow = 0i16;
+
+    for (index, byte) in right.iter_mut().enumerate() {
+        let minuend = *left.get(index).unwrap_or(&0);
+        let mut difference = minuend as i16 - *byte as i16 - borrow;
+
+        borrow = 0;
+
+        if difference < 0 {
+            difference += 256;
+            borrow = 1;
+        }
+
+        *byte = difference as u8;
+    }
+
+    debug_assert_eq!(borrow, 0, "magnitude subtraction underflow");
+
+    trim(right);
+}
+
+/// Returns the largest power of `radix` that fits in a word, and its exponent.
+pub(crate) fn largest_power(radix: u64) -> (u64, usize) {
+    let mut power = radix;
+    let mut exponent = 1;
+
+    while let Some(next) = power.checked_mul(radix) {
+        power = next;
+        exponent += 1;
+    }
+
+    (power, exponent)
+}
+
+/// Multiply a magnitude by a single word in place.
+pub(crate) fn mul_word_assign(data: &mut Vec<u8>, multiplier: u64) {
+    let mut carry = 0u128;
+
+    for byte in data.iter_mut() {
+        let product = *byte as u128 * multiplier as u128 + carry;
+
+        *byte = product as u8;
+        carry = product >> 8;
+    }
+
+    while carry > 0 {
+        data.push(carry as u8);
+        carry >>= 8;
+    }
+
+    trim(data);
+}
+
+/// Divide a magnitude by a single word in place and return the remainder.
+///
+/// Panics if `divisor` is zero.
+pub(crate) fn div_word_assign(data: &mut Vec<u8>, divisor: u64) -> u64 {
+    if divisor == 0 {
+        panic!("attempt to divide by zero");
+    }
+
+    // Since the representation is little-endian, we iterate from the most significant byte.
+    let remainder = if divisor < 1 << 56 {
+        // The partial remainder fits in 64 bits, which avoids 128-bit divisions.
+        let mut remainder = 0u64;
+
+        for byte in data.iter_mut().rev() {
+            let current = remainder << 8 | *byte as u64;
+
+            *byte = (current / divisor) as u8;
+            remainder = current % divisor;
+        }
+
+        remainder
+    } else {
+        let mut remainder = 0u128;
+
+        for byte in data.iter_mut().rev() {
+            let current = remainder << 8 | *byte as u128;
+
+            *byte = (current / divisor as u128) as u8;
+            remainder = current % divisor as u128;
+        }
+
+        remainder as u64
+    };
+
+    trim(data);
+
+    remainder
+}
+
+/// Divide a word magnitude by a single word in place and return the remainder.
+pub(crate) fn div_word_assign_words(words: &mut Vec<u64>, divisor: u64) -> u64 {
+    let mut remainder = 0u128;
+
+    for word in words.iter_mut().rev() {
+        let current = remainder << 64 | *word as u128;
+
+        *word = (current / divisor as u128) as u64;
+        remainder = current % divisor as u128;
+    }
+
+    trim_words(words);
+
+    remainder as u64
+}
+
+/// Multiply two magnitudes.
+pub(crate) fn mul(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let left = to_words(left);
+    let right = to_words(right);
+
+    from_words(&mul_words(&left, &right))
+}
+
+/// Divide two magnitudes and return the pair (quotient, remainder).
+///
+/// Panics if `divisor` is zero.
+pub(crate) fn div_rem(dividend: &[u8], divisor: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let dividend = to_words(dividend);
+    let divisor = to_words(divisor);
+
+    if divisor.is_empty() {
+        panic!("attempt to divide by zero");
+    }
+
+    let (quotient, remainder) = div_rem_words(&dividend, &divisor);
+
+    (from_words(&quotient), from_words(&remainder))
+}
+
+/// Shift a magnitude to the left by `bits`.
+pub(crate) fn shl(data: &[u8], bits: usize) -> Vec<u8> {
+    let data = significant(data);
+
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let bytes = bits / 8;
+    let bits = (bits % 8) as u32;
+
+    let mut result = vec![0u8; bytes];
+    result.reserve(data.len() + 1);
+
+    if bits == 0 {
+        result.extend_from_slice(data);
+    } else {
+        let mut carry = 0u8;
+
+        for &byte in data {
+            result.push(byte << bits | carry);
+            carry = byte >> (8 - bits);
+        }
+
+        result.push(carry);
+    }
+
+    trim(&mut result);
+
+    result
+}
+
+/// Shift a magnitude to the right by `bits`, discarding the bits shifted out.
+pub(crate) fn shr(data: &[u8], bits: usize) -> Vec<u8> {
+    let data = significant(data);
+    let bytes = bits / 8;
+
+    if bytes >= data.len() {
+        return Vec::new();
+    }
+
+    let bits = (bits % 8) as u32;
+    let data = &data[bytes..];
+
+    let mut result: Vec<u8> = if bits == 0 {
+        data.to_vec()
+    } else {
+        (0..data.len())
+            .map(|index| {
+                let high = data.get(index + 1).map_or(0, |&byte| byte << (8 - bits));
+
+                data[index] >> bits | high
+            })
+            .collect()
+    };
+
+    trim(&mut result);
+
+    result
+}
+
+/// Returns `true` if any of the lowest `bits` bits of the magnitude is set.
+pub(crate) fn has_low_bits(data: &[u8], bits: usize) -> bool {
+    let bytes = bits / 8;
+
+    if data[..bytes.min(data.len())].iter().any(|&byte| byte != 0) {
+        return true;
+    }
+
+    let bits = bits % 8;
+
+    bits > 0
+        && data
+            .get(bytes)
+            .is_some_and(|&byte| byte & ((1 << bits) - 1) != 0)
+}
+
+/// Pack the bytes into 64-bit little-endian words, without high zero words.
+pub(crate) fn to_words(data: &[u8]) -> Vec<u64> {
+    significant(data)
+        .chunks(8)
+        .map(|chunk| {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+
+            u64::from_le_bytes(word)
+        })
+        .collect()
+}
+
+/// Unpack 64-bit little-endian words into bytes, without high zero bytes.
+pub(crate) fn from_words(words: &[u64]) -> Vec<u8> {
+    let mut data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+
+    trim(&mut data);
+
+    data
+}
+
+/// Remove the high zero words.
+fn trim_words(words: &mut Vec<u64>) {
+    while words.last() == Some(&0) {
+        words.pop();
+    }
+}
+
+/// Returns the words without the high zero words.
+fn significant_words(words: &[u64]) -> &[u64] {
+    &words[..words.len() - words.iter().rev().take_while(|&&word| word == 0).count()]
+}
+
+/// Compare two word magnitudes.
+fn compare_words(left: &[u64], right: &[u64]) -> Ordering {
+    let left = significant_words(left);
+    let right = significant_words(right);
+
+    left.len()
+        .cmp(&right.len())
+        .then_with(|| left.iter().rev().cmp(right.iter().rev()))
+}
+
+/// Add `right` into `accumulator`, starting at word `offset`.
+/// The accumulator must be long enough to hold the result.
+pub(crate) fn add_words_at(accumulator: &mut [u64], right: &[u64], offset: usize) {
+    let mut carry = false;
+
+    for (index, &word) in right.iter().enumerate() {
+        let (sum, overflow_a) = accumulator[offset + index].overflowing_add(word);
+        let (sum, overflow_b) = sum.overflowing_add(carry as u64);
+
+        accumulator[offset + index] = sum;
+        carry = overflow_a || overflow_b;
+    }
+
+    let mut index = offset + right.len();
+
+    while carry {
+        let (sum, overflow) = accumulator[index].overflowing_add(1);
+
+        accumulator[index] = sum;
+        carry = overflow;
+        index += 1;
+    }
+}
+
+/// Subtract `right` from `left` in place. `left` must not be smaller than `right`.
+fn sub_words_assign(left: &mut [u64], right: &[u64]) {
+    let mut borrow = false;
+
+    for (index, &word) in right.iter().enumerate() {
+        let (difference, overflow_a) = left[index].overflowing_sub(word);
+        let (difference, overflow_b) = difference.overflowing_sub(borrow as u64);
+
+        left[index] = difference;
+        borrow = overflow_a || overflow_b;
+    }
+
+    let mut index = right.len();
+
+    while borrow {
+        let (difference, overflow) = left[index].overflowing_sub(1);
+
+        left[index] = difference;
+        borrow = overflow;
+        index += 1;
+    }
+}
+
+/// Multiply two word magnitudes.
+pub(crate) fn mul_words(left: &[u64], right: &[u64]) -> Vec<u64> {
+    // Make `left` the longest operand.
+    let (left, right) = if left.len() >= right.len() {
+        (left, right)
+    } else {
+        (right, left)
+    };
+
+    if right.is_empty() {
+        return Vec::new();
+    }
+
+    let mut result = if right.len() < KARATSUBA_THRESHOLD {
+        mul_words_schoolbook(left, right)
+    } else if left.len() >= 2 * right.len() {
+        // Unbalanced operands: multiply `right` by slices of `left` as long as itself.
+        let mut result = vec![0u64; left.len() + right.len()];
+
+        for (index, chunk) in left.chunks(right.len()).enumerate() {
+            add_words_at(&mut result, &mul_words(chunk, right), index * right.len());
+        }
+
+        result
+    } else if right.len() < TOOM_3_THRESHOLD {
+        mul_words_karatsuba(left, right)
+    } else if right.len() < NTT_THRESHOLD {
+        mul_words_toom_3(left, right)
+    } else {
+        mul_words_ntt(left, right)
+    };
+
+    trim_words(&mut result);
+
+    result
+}
+
+fn mul_words_schoolbook(left: &[u64], right: &[u64]) -> Vec<u64> {
+    let mut result = vec![0u64; left.len() + right.len()];
+
+    for (i, &left_word) in left.iter().enumerate() {
+        if left_word == 0 {
+            continue;
+        }
+
+        let (row, top) = result[i..=i + right.len()].split_at_mut(right.len());
+        let mut carry = 0u64;
+
+        for (word, &right_word) in row.iter_mut().zip(right) {
+            let product = left_word as u128 * right_word as u128 + *word as u128 + carry as u128;
+
+            *word = product as u64;
+            carry = (product >> 64) as u64;
+        }
+
+        top[0] = carry;
+    }
+
+    result
+}
+
+/// Karatsuba multiplication. `right` must be longer than half of `left`.
+fn mul_words_karatsuba(left: &[u64], right: &[u64]) -> Vec<u64> {
+    let half = left.len() / 2;
+
+    let (left_low, left_high) = left.split_at(half);
+    let (right_low, right_high) = right.split_at(half);
+
+    let low = mul_words(left_low, right_low);
+    let high = mul_words(left_high, right_high);
+
+    let mut left_sum = vec![0u64; half.max(left_high.len()) + 1];
+    left_sum[..left_low.len()].copy_from_slice(left_low);
+    add_words_at(&mut left_sum, left_high, 0);
+
+    let mut right_sum = vec![0u64; half.max(right_high.len()) + 1];
+    right_sum[..right_low.len()].copy_from_slice(right_low);
+    add_words_at(&mut right_sum, right_high, 0);
+
+    // (a + b)(c + d) - ac - bd = ad + bc
+    let mut middle = mul_words(&left_sum, &right_sum);
+    sub_words_assign(&mut middle, &low);
+    sub_words_assign(&mut middle, &high);
+    trim_words(&mut middle);
+
+    let mut result = vec![0u64; left.len() + right.len() + 1];
+
+    add_words_at(&mut result, &low, 0);
+    add_words_at(&mut result, &middle, half);
+    add_words_at(&mut result, &high, 2 * half);
+
+    result
+}
+
+/// A word magnitude and whether it is negative, for the intermediate values of Toom-3.
+type SignedWords = (Vec<u64>, bool);
+
+/// Toom-Cook 3-way multiplication, evaluated at 0, 1, -1, -2 and infinity with Bodrato's
+/// sequence. `right` must be longer than half of `left`.
+fn mul_words_toom_3(left: &[u64], right: &[u64]) -> Vec<u64> {
+    let third = left.len().div_ceil(3);
+
+    let split = |words: &[u64]| -> [SignedWords; 3] {
+        let mut parts = words.chunks(third).map(|part| {
+            let mut part = part.to_vec();
+            trim_words(&mut part);
+
+            (part, false)
+        });
+
+        [(); 3].map(|_| parts.next().unwrap_or_default())
+    };
+
+    let [a0, a1, a2] = split(left);
+    let [b0, b1, b2] = split(right);
+
+    let [a_one, a_minus_one, a_minus_two] = toom_3_evaluate(&a0, &a1, &a2);
+    let [b_one, b_minus_one, b_minus_two] = toom_3_evaluate(&b0, &b1, &b2);
+
+    let r0 = mul_signed_words(&a0, &b0);
+    let r1 = mul_signed_words(&a_one, &b_one);
+    let r_minus_one = mul_signed_words(&a_minus_one, &b_minus_one);
+    let r_minus_two = mul_signed_words(&a_minus_two, &b_minus_two);
+    let r_infinity = mul_signed_words(&a2, &b2);
+
+    // Interpolate the coefficients of the product polynomial.
+    let r3 = div_signed_words(&sub_signed_words(&r_minus_two, &r1), 3);
+    let r1 = shr_signed_words(&sub_signed_words(&r1, &r_minus_one));
+    let r2 = sub_signed_words(&r_minus_one, &r0);
+    let r3 = add_signed_words(
+        &shr_signed_words(&sub_signed_words(&r2, &r3)),
+        &shl_signed_words(&r_infinity),
+    );
+    let r2 = sub_signed_words(&add_signed_words(&r2, &r1), &r_infinity);
+    let r1 = sub_signed_words(&r1, &r3);
+
+    let mut result = vec![0u64; left.len() + right.len() + 1];
+
+    for (index, coefficient) in [r0, r1, r2, r3, r_infinity].iter().enumerate() {
+        add_words_at(&mut result, &coefficient.0, index * third);
+    }
+
+    result
+}
+
+/// Returns the values at 1, -1 and -2 of the polynomial with the given coefficients.
+fn toom_3_evaluate(a0: &SignedWords, a1: &SignedWords, a2: &SignedWords) -> [SignedWords; 3] {
+    let sum = add_signed_words(a0, a2);
+
+    let at_one = add_signed_words(&sum, a1);
+    let at_minus_one = sub_signed_words(&sum, a1);
+    let at_minus_two =
+        sub_signed_words(&shl_signed_words(&add_signed_words(&at_minus_one, a2)), a0);
+
+    [at_one, at_minus_one, at_minus_two]
+}
+
+fn add_signed_words(left: &SignedWords, right: &SignedWords) -> SignedWords {
+    let ((left, left_negative), (right, right_negative)) = (left, right);
+
+    if left_negative == right_negative {
+        let (longer, shorter) = if left.len() >= right.len() {
+            (left, right)
+        } else {
+            (right, left)
+        };
+
+        let mut sum = longer.clone();
+        sum.push(0);
+        add_words_at(&mut sum, shorter, 0);
+        trim_words(&mut sum);
+
+        return (sum, *left_negative);
+    }
+
+    let (larger, smaller, negative) = match compare_words(left, right) {
+        Ordering::Less => (right, left, *right_negative),
+        _ => (left, right, *left_negative),
+    };
+
+    let mut difference = larger.clone();
+    sub_words_assign(&mut difference, smaller);
+    trim_words(&mut difference);
+
+    let negative = negative && !difference.is_empty();
+
+    (difference, negative)
+}
+
+fn sub_signed_words(left: &SignedWords, right: &SignedWords) -> SignedWords {
+    add_signed_words(left, &(right.0.clone(), !right.1))
+}
+
+fn mul_signed_words(left: &SignedWords, right: &SignedWords) -> SignedWords {
+    let product = mul_words(&left.0, &right.0);
+    let negative = left.1 != right.1 && !product.is_empty();
+
+    (product, negative)
+}
+
+/// Multiply by 2.
+fn shl_signed_words(value: &SignedWords) -> SignedWords {
+    (shl_words(&value.0, 1), value.1)
+}
+
+/// Divide by 2, which must be exact.
+fn shr_signed_words(value: &SignedWords) -> SignedWords {
+    let mut words = shr_words(&value.0, 1);
+    trim_words(&mut words);
+
+    (words, value.1)
+}
+
+/// Divide by a word, which must be exact.
+fn div_signed_words(value: &SignedWords, divisor: u64) -> SignedWords {
+    (div_rem_word(&value.0, divisor).0, value.1)
+}
+
+/// Multiplication by a number-theoretic transform modulo `NTT_PRIME`, over pieces of the words
+/// small enough for the coefficients of the product to stay below the prime.
+fn mul_words_ntt(left: &[u64], right: &[u64]) -> Vec<u64> {
+    // The shortest transform, then the smallest pieces for it.
+    let (length, bits) = (16..=24)
+        .filter_map(|bits| {
+            let left_pieces = (left.len() * 64).div_ceil(bits);
+            let right_pieces = (right.len() * 64).div_ceil(bits);
+
+            // Every coefficient is a sum of at most this many products of two pieces.
+            let terms = left_pieces.min(right_pieces) as u128;
+
+            (terms << (2 * bits) < NTT_PRIME as u128)
+                .then(|| ((left_pieces + right_pieces).next_power_of_two(), bits))
+        })
+        .min()
+        .unwrap();
+
+    let transform = |words: &[u64]| {
+        let mut values = to_pieces(words, bits, length);
+        ntt(&mut values, false);
+
+        values
+    };
+
+    let mut values = transform(left);
+
+    // A square needs a single forward transform.
+    if std::ptr::eq(left, right) {
+        for value in values.iter_mut() {
+            *value = mul_mod(*value, *value);
+        }
+    } else {
+        for (value, other) in values.iter_mut().zip(transform(right)) {
+            *value = mul_mod(*value, other);
+        }
+    }
+
+    ntt(&mut values, true);
+
+    from_pieces(&values, bits, left.len() + right.len())
+}
+
+/// Split words into `length` pieces of `bits` bits, least significant first.
+fn to_pieces(words: &[u64], bits: usize, length: usize) -> Vec<u64> {
+    let mask = (1u64 << bits) - 1;
+
+    let mut values = Vec::with_capacity(length);
+    let mut buffer = 0u128;
+    let mut buffered = 0;
+
+    for &word in words {
+        buffer |= (word as u128) << buffered;
+        buffered += 64;
+
+        while buffered >= bits {
+            values.push(buffer as u64 & mask);
+            buffer >>= bits;
+            buffered -= bits;
+        }
+    }
+
+    values.push(buffer as u64);
+    values.resize(length, 0);
+
+    values
+}
+
+/// Returns `length` words holding the sum of the coefficients, each one shifted by `bits`
+/// bits more than the previous one.
+fn from_pieces(coefficients: &[u64], bits: usize, length: usize) -> Vec<u64> {
+    let mut result = Vec::with_capacity(length);
+    let mut carry = 0u128;
+    let mut buffer = 0u128;
+    let mut buffered = 0;
+
+    for &coefficient in coefficients {
+        carry += coefficient as u128;
+        buffer |= (carry & ((1 << bits) - 1)) << buffered;
+        carry >>= bits;
+        buffered += bits;
+
+        if buffered >= 64 {
+            result.push(buffer as u64);
+            buffer >>= 64;
+            buffered -= 64;
+        }
+
+        if result.len() == length {
+            return result;
+        }
+    }
+
+    result.push(buffer as u64);
+    result.resize(length, 0);
+
+    result
+}
+
+/// Transform `values` in place, or transform them back if `inverse`. The length must be a
+/// power of two.
+fn ntt(values: &mut [u64], inverse: bool) {
+    let length = values.len();
+
+    // Bit-reversal permutation.
+    let mut reversed = 0;
+
+    for index in 1..length {
+        let mut bit = length >> 1;
+
+        while reversed & bit != 0 {
+            reversed ^= bit;
+            bit >>= 1;
+        }
+
+        reversed |= bit;
+
+        if index < reversed {
+            values.swap(index, reversed);
+        }
+    }
+
+    let mut half = 1;
+
+    while half < length {
+        let mut root = pow_mod(NTT_GENERATOR, (NTT_PRIME - 1) / (2 * half) as u64);
+
+        if inverse {
+            root = pow_mod(root, NTT_PRIME - 2);
+        }
+
+        let twiddles: Vec<u64> =
+            std::iter::successors(Some(1), |&twiddle| Some(mul_mod(twiddle, root)))
+                .take(half)
+                .collect();
+
+        for block in values.chunks_mut(2 * half) {
+            let (low, high) = block.split_at_mut(half);
+
+            for ((low, high), &twiddle) in low.iter_mut().zip(high.iter_mut()).zip(&twiddles) {
+                let product = mul_mod(*high, twiddle);
+
+                *high = sub_mod(*low, product);
+                *low = add_mod(*low, product);
+            }
+        }
+
+        half *= 2;
+    }
+
+    if inverse {
+        let scale = pow_mod(length as u64, NTT_PRIME - 2);
+
+        for value in values.iter_mut() {
+            *value = mul_mod(*value, scale);
+        }
+    }
+}
+
+/// Returns `left + right` modulo `NTT_PRIME`, for operands below it.
+fn add_mod(left: u64, right: u64) -> u64 {
+    let (sum, overflow) = left.overflowing_add(right);
+
+    // 2^64 is 2^32 - 1 modulo the prime.
+    if overflow {
+        sum + 0xFFFF_FFFF
+    } else if sum >= NTT_PRIME {
+        sum - NTT_PRIME
+    } else {
+        sum
+    }
+}
+
+/// Returns `left - right` modulo `NTT_PRIME`, for operands below it.
+fn sub_mod(left: u64, right: u64) -> u64 {
+    let (difference, borrow) = left.overflowing_sub(right);
+
+    if borrow {
+        difference.wrapping_sub(0xFFFF_FFFF)
+    } else {
+        difference
+    }
+}
+
+/// Returns `left * right` modulo `NTT_PRIME`, for operands below it.
+fn mul_mod(left: u64, right: u64) -> u64 {
+    let product = left as u128 * right as u128;
+
+    // With 2^64 = 2^32 - 1 and 2^96 = -1 modulo the prime, the product
+    // low + 2^64 * (middle + 2^32 * high) is low + (2^32 - 1) * middle - high.
+    let low = product as u64;
+    let middle = (product >> 64) as u64 & 0xFFFF_FFFF;
+    let high = (product >> 96) as u64;
+
+    let (mut value, borrow) = low.overflowing_sub(high);
+
+    if borrow {
+        value = value.wrapping_sub(0xFFFF_FFFF);
+    }
+
+    let (value, overflow) = value.overflowing_add(middle * 0xFFFF_FFFF);
+    let value = if overflow { value + 0xFFFF_FFFF } else { value };
+
+    if value >= NTT_PRIME {
+        value - NTT_PRIME
+    } else {
+        value
+    }
+}
+
+/// Returns `base^exponent` modulo `NTT_PRIME`.
+fn pow_mod(mut base: u64, mut exponent: u64) -> u64 {
+    let mut result = 1;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base);
+        }
+
+        base = mul_mod(base, base);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+/// Divide two word magnitudes and return the pair (quotient, remainder).
+/// The divisor must not be zero.
+pub(crate) fn div_rem_words(dividend: &[u64], divisor: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let divisor = significant_words(divisor);
+    let dividend = significant_words(dividend);
+
+    if divisor.len() > BURNIKEL_ZIEGLER_THRESHOLD
+        && dividend.len() > divisor.len() + BURNIKEL_ZIEGLER_THRESHOLD
+    {
+        return div_rem_burnikel_ziegler(dividend, divisor);
+    }
+
+    div_rem_schoolbook(dividend, divisor)
+}
+
+/// Quadratic division, for operands where the recursive division does not pay off.
+fn div_rem_schoolbook(dividend: &[u64], divisor: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let divisor = significant_words(divisor);
+
+    if compare_words(dividend, divisor) == Ordering::Less {
+        let mut remainder = dividend.to_vec();
+        trim_words(&mut remainder);
+
+        return (Vec::new(), remainder);
+    }
+
+    if divisor.len() == 1 {
+        let (quotient, remainder) = div_rem_word(dividend, divisor[0]);
+
+        return (
+            quotient,
+            if remainder == 0 {
+                vec![]
+            } else {
+                vec![remainder]
+            },
+        );
+    }
+
+    div_rem_knuth(dividend, divisor)
+}
+
+/// Burnikel and Ziegler's recursive division ("Fast Recursive Division", 1998), which costs
+/// about two multiplications of the divisor size instead of a quadratic number of steps.
+fn div_rem_burnikel_ziegler(dividend: &[u64], divisor: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    // Pick a block size `length = j * 2^levels` with `j` small enough for the schoolbook
+    // division, so that the recursion can halve the blocks all the way down.
+    let mut levels = 0;
+    let mut j = divisor.len();
+
+    while j > BURNIKEL_ZIEGLER_THRESHOLD {
+        j = j.div_ceil(2);
+        levels += 1;
+    }
+
+    let length = j << levels;
+
+    // Scale both operands so that the divisor fills the block with its top bit set.
+    let word_shift = length - divisor.len();
+    let bit_shift = divisor.last().unwrap().leading_zeros();
+
+    let divisor = shift_words_left(divisor, word_shift, bit_shift);
+    let mut dividend = shift_words_left(dividend, word_shift, bit_shift);
+
+    // At least one high zero word keeps the top block below the divisor.
+    let blocks = dividend.len() / length + 1;
+    dividend.resize(blocks * length, 0);
+
+    let mut quotient = vec![0u64; (blocks - 1) * length];
+    let mut remainder = dividend[(blocks - 1) * length..].to_vec();
+
+    for block in (0..blocks - 1).rev() {
+        let mut current = dividend[block * length..(block + 1) * length].to_vec();
+        remainder.resize(length, 0);
+        current.extend_from_slice(&remainder);
+
+        let (block_quotient, block_remainder) = div_two_blocks_by_one(&current, &divisor, length);
+
+        quotient[block * length..block * length + block_quotient.len()]
+            .copy_from_slice(&block_quotient);
+        remainder = block_remainder;
+    }
+
+    remainder.resize(remainder.len().max(word_shift), 0);
+
+    let mut remainder = shr_words(&remainder[word_shift..], bit_shift);
+
+    trim_words(&mut quotient);
+    trim_words(&mut remainder);
+
+    (quotient, remainder)
+}
+
+/// Divide a dividend of up to `2 * length` words by a divisor of `length` words whose top bit
+/// is set. The dividend must be lower than the divisor times `2^(64 * length)`.
+fn div_two_blocks_by_one(dividend: &[u64], divisor: &[u64], length: usize) -> (Vec<u64>, Vec<u64>) {
+    if length % 2 == 1 || length <= BURNIKEL_ZIEGLER_THRESHOLD {
+        return div_rem_schoolbook(dividend, divisor);
+    }
+
+    let half = length / 2;
+
+    let mut dividend = dividend.to_vec();
+    dividend.resize(2 * length, 0);
+
+    // Divide the three high quarters, then the remainder followed by the low quarter.
+    let (high, remainder) = div_three_halves_by_two(&dividend[half..], divisor, half);
+
+    let mut next = dividend[..half].to_vec();
+    next.extend_from_slice(&remainder);
+    next.resize(3 * half, 0);
+
+    let (low, remainder) = div_three_halves_by_two(&next, divisor, half);
+
+    let mut quotient = low;
+    quotient.resize(half, 0);
+    quotient.extend_from_slice(&high);
+    trim_words(&mut quotient);
+
+    (quotient, remainder)
+}
+
+/// Divide a dividend of `3 * half` words by a divisor of `2 * half` words whose top bit is
+/// set. The dividend must be lower than the divisor times `2^(64 * half)`.
+fn div_three_halves_by_two(dividend: &[u64], divisor: &[u64], half: usize) -> (Vec<u64>, Vec<u64>) {
+    let (divisor_low, divisor_high) = divisor.split_at(half);
+    let (dividend_low, dividend_high) = dividend.split_at(half);
+
+    // Estimate the quotient from the high halves; it is at most two too large.
+    let (mut quotient, high_remainder) =
+        if compare_words(&dividend_high[half..], divisor_high) == Ordering::Less {
+            div_two_blocks_by_one(dividend_high, divisor_high, half)
+        } else {
+            // The estimate is 2^(64 * half) - 1, which leaves
+            // dividend_high - divisor_high * 2^(64 * half) + divisor_high.
+            let mut remainder = dividend_high.to_vec();
+            remainder.push(0);
+            add_words_at(&mut remainder, divisor_high, 0);
+
+            let mut shifted = vec![0u64; half];
+            shifted.extend_from_slice(divisor_high);
+            sub_words_assign(&mut remainder, &shifted);
+
+            (vec![u64::MAX; half], remainder)
+        };
+
+    let mut remainder = dividend_low.to_vec();
+    remainder.extend_from_slice(&high_remainder);
+    trim_words(&mut remainder);
+
+    let correction = mul_words(&quotient, divisor_low);
+
+    if compare_words(&remainder, &correction) != Ordering::Less {
+        sub_words_assign(&mut remainder, &correction);
+        trim_words(&mut remainder);
+
+        return (quotient, remainder);
+    }
+
+    // The remainder went negative: add the divisor back until it is not.
+    let mut deficit = correction;
+    sub_words_assign(&mut deficit, &remainder);
+    trim_words(&mut deficit);
+
+    loop {
+        sub_words_assign(&mut quotient, &[1]);
+
+        if compare_words(divisor, &deficit) != Ordering::Less {
+            let mut remainder = divisor.to_vec();
+            sub_words_assign(&mut remainder, &deficit);
+            trim_words(&mut remainder);
+            trim_words(&mut quotient);
+
+            return (quotient, remainder);
+        }
+
+        sub_words_assign(&mut deficit, divisor);
+        trim_words(&mut deficit);
+    }
+}
+
+/// Shift a word magnitude to the left by `bits`.
+pub(crate) fn shl_words_by(words: &[u64], bits: usize) -> Vec<u64> {
+    let mut result = shift_words_left(words, bits / 64, (bits % 64) as u32);
+    trim_words(&mut result);
+
+    result
+}
+
+/// Split a word magnitude into its value shifted to the right by `bits`, and its lowest
+/// `bits` bits.
+pub(crate) fn split_words_at(words: &[u64], bits: usize) -> (Vec<u64>, Vec<u64>) {
+    let (low_words, high_words) = words.split_at((bits / 64).min(words.len()));
+    let bit_shift = (bits % 64) as u32;
+
+    let mut high = shr_words(high_words, bit_shift);
+    let mut low = low_words.to_vec();
+
+    if let Some(&word) = high_words.first().filter(|_| bit_shift > 0) {
+        low.push(word & ((1 << bit_shift) - 1));
+    }
+
+    trim_words(&mut high);
+    trim_words(&mut low);
+
+    (high, low)
+}
+
+/// Shift words to the left by whole words and then by less than a word.
+fn shift_words_left(words: &[u64], word_shift: usize, bit_shift: u32) -> Vec<u64> {
+    let mut result = vec![0u64; word_shift];
+    result.extend(shl_words(words, bit_shift));
+
+    result
+}
+
+/// Divide a word magnitude by a single word and return the pair (quotient, remainder).
+pub(crate) fn div_rem_word(dividend: &[u64], divisor: u64) -> (Vec<u64>, u64) {
+    let mut quotient = vec![0u64; dividend.len()];
+    let mut remainder = 0u128;
+
+    for index in (0..dividend.len()).rev() {
+        let current = remainder << 64 | dividend[index] as u128;
+
+        quotient[index] = (current / divisor as u128) as u64;
+        remainder = current % divisor as u128;
+    }
+
+    trim_words(&mut quotient);
+
+    (quotient, remainder as u64)
+}
+
+/// Knuth's algorithm D (The Art of Computer Programming, vol. 2, 4.3.1).
+/// The divisor must have at least two words and must not exceed the dividend.
+fn div_rem_knuth(dividend: &[u64], divisor: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    // Normalize so that the top bit of the divisor is set.
+    let shift = divisor.last().unwrap().leading_zeros();
+
+    let divisor = shl_words(divisor, shift);
+    let mut remainder = shl_words(dividend, shift);
+    remainder.resize(dividend.len() + 1, 0);
+
+    let length = divisor.len();
+    let top = divisor[length - 1] as u128;
+    let second = divisor[length - 2] as u128;
+
+    let mut quotient = vec![0u64; remainder.len() - length];
+
+    for j in (0..quotient.len()).rev() {
+        let numerator = (remainder[j + length] as u128) << 64 | remainder[j + length - 1] as u128;
+
+        let mut estimate = numerator / top;
+        let mut rest = numerator % top;
+
+        while estimate > u64::MAX as u128
+            || estimate * second > (rest << 64 | remainder[j + length - 2] as u128)
+        {
+            estimate -= 1;
+            rest += top;
+
+            if rest > u64::MAX as u128 {
+                break;
+            }
+        }
+
+        // Multiply and subtract.
+        let mut carry = 0u64;
+        let mut borrow = false;
+
+        for index in 0..length {
+            let product = estimate * divisor[index] as u128 + carry as u128;
+            carry = (product >> 64) as u64;
+
+            let (difference, overflow_a) = remainder[j + index].overflowing_sub(product as u64);
+            let (difference, overflow_b) = difference.overflowing_sub(borrow as u64);
+
+            remainder[j + index] = difference;
+            borrow = overflow_a || overflow_b;
+        }
+
+        let (difference, overflow_a) = remainder[j + length].overflowing_sub(carry);
+        let (difference, overflow_b) = difference.overflowing_sub(borrow as u64);
+
+        remainder[j + length] = difference;
+
+        // The estimate was one too large: add the divisor back.
+        if overflow_a || overflow_b {
+            estimate -= 1;
+
+            let mut carry = false;
+
+            for index in 0..length {
+                let (sum, overflow_a) = remainder[j + index].overflowing_add(divisor[index]);
+                let (sum, overflow_b) = sum.overflowing_add(carry as u64);
+
+                remainder[j + index] = sum;
+                carry = overflow_a || overflow_b;
+            }
+
+            remainder[j + length] = remainder[j + length].wrapping_add(carry as u64);
+        }
+
+        quotient[j] = estimate as u64;
+    }
+
+    remainder.truncate(length);
+
+    let mut remainder = shr_words(&remainder, shift);
+
+    trim_words(&mut quotient);
+    trim_words(&mut remainder);
+
+    (quotient, remainder)
+}
+
+/// Shift words to the left by less than a word, keeping the same length plus a carry word
+/// when it is not zero.
+fn shl_words(words: &[u64], shift: u32) -> Vec<u64> {
+    if shift == 0 {
+        return words.to_vec();
+    }
+
+    let mut result = Vec::with_capacity(words.len() + 1);
+    let mut carry = 0u64;
+
+    for &word in words {
+        result.push(word << shift | carry);
+        carry = word >> (64 - shift);
+    }
+
+    if carry > 0 {
+        result.push(carry);
+    }
+
+    result
+}
+
+/// Shift words to the right by less than a word.
+fn shr_words(words: &[u64], shift: u32) -> Vec<u64> {
+    if shift == 0 {
+        return words.to_vec();
+    }
+
+    (0..words.len())
+        .map(|index| {
+            let high = words.get(index + 1).map_or(0, |&word| word << (64 - shift));
+
+            words[index] >> shift | high
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod multiplication {
+        use super::*;
+
+        #[test]
+        fn should_match_schoolbook_above_karatsuba_threshold() {
+            // Given
+            let left: Vec<u64> = (1..=97u64)
+                .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+                .collect();
+            let right: Vec<u64> = (1..=71u64)
+                .map(|i| i.wrapping_mul(0xC2B2_AE3D_27D4_EB4F))
+                .collect();
+
+            let mut expected = mul_words_schoolbook(&left, &right);
+            trim_words(&mut expected);
+
+            // When
+            let result = mul_words(&left, &right);
+
+            // Then
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn should_match_schoolbook_above_toom_3_threshold() {
+            // Given: negative intermediate values, and a right operand with an empty top third.
+            let left: Vec<u64> = (1..=700u64)
+                .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+                .collect();
+            let right: Vec<u64> = (1..=400u64)
+                .map(|i| u64::MAX - i.wrapping_mul(0xC2B2_AE3D_27D4_EB4F) % 7)
+                .collect();
+
+            for right in [&right[..], &right[..360]] {
+                let mut expected = mul_words_schoolbook(&left, right);
+                trim_words(&mut expected);
+
+                // When
+                let result = mul_words(&left, right);
+
+                // Then
+                assert_eq!(result, expected);
+            }
+        }
+    }
+
+    mod number_theoretic_transform {
+        use super::*;
+
+        #[test]
+        fn should_reduce_modulo_the_prime() {
+            // Given
+            let prime = NTT_PRIME as u128;
+
+            for (left, right) in [
+                (NTT_PRIME - 1, NTT_PRIME - 1),
+                (NTT_PRIME - 1, 2),
+                (0xFFFF_FFFF, 0x1_0000_0000),
+                (0x1234_5678_9ABC_DEF0, 0xFEDC_BA98_7654_3210 % NTT_PRIME),
+            ] {
+                // When / Then
+                assert_eq!(
+                    mul_mod(left, right) as u128,
+                    left as u128 * right as u128 % prime
+                );
+                assert_eq!(
+                    add_mod(left, right) as u128,
+                    (left as u128 + right as u128) % prime
+                );
+                assert_eq!(
+                    sub_mod(left, right) as u128,
+                    (left as u128 + prime - right as u128) % prime
+                );
+            }
+        }
+
+        #[test]
+        fn should_match_toom_3() {
+            // Given: words full of ones for the largest coefficients, and lengths for 16-bit
+            // and for 17-bit pieces.
+            let left: Vec<u64> = (1..=4200u64)
+                .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+                .collect();
+            let right = vec![u64::MAX; 4100];
+
+            for right in [&right[..], &left[..3001], &left[..]] {
+                let mut expected = mul_words_toom_3(&left, right);
+                trim_words(&mut expected);
+
+                // When
+                let result = mul_words(&left, right);
+
+                // Then
+                assert_eq!(result, expected);
+            }
+        }
+    }
+
+    mod division {
+        use super::*;
+
+        #[test]
+        fn should_recompose_the_dividend() {
+            // Given
+            let dividend: Vec<u64> = (1..=40u64)
+                .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+                .collect();
+            let divisor: Vec<u64> = (1..=13u64)
+                .map(|i| i.wrapping_mul(0xC2B2_AE3D_27D4_EB4F))
+                .collect();
+
+            // When
+            let (quotient, remainder) = div_rem_words(&dividend, &divisor);
+
+            // Then
+            let mut recomposed = mul_words(&quotient, &divisor);
+            recomposed.resize(dividend.len() + 1, 0);
+            add_words_at(&mut recomposed, &remainder, 0);
+            trim_words(&mut recomposed);
+
+            assert_eq!(recomposed, dividend);
+            assert_eq!(compare_words(&remainder, &divisor), Ordering::Less);
+        }
+
+        #[test]
+        fn should_divide_by_a_divisor_just_above_a_power_of_two() {
+            // Given
+            let dividend = vec![0, 0, 0x8000_0000_0000_0000];
+            let divisor = vec![1, 0x8000_0000_0000_0000];
+
+            // When
+            let (quotient, remainder) = div_rem_words(&dividend, &divisor);
+
+            // Then
+            assert_eq!(quotient, vec![u64::MAX]);
+            assert_eq!(remainder, vec![1, 0x7FFF_FFFF_FFFF_FFFF]);
+        }
+    }
+}
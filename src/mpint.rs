@@ -0,0 +1,238 @@
+//! The multi-precision integers of the SSH (RFC 4251) and OpenPGP (RFC 4880) wire formats.
+
+use std::cmp::Ordering;
+
+use crate::decode::{self, DecodeError, DecodeErrorKind};
+use crate::{BigInt, Sign};
+
+impl BigInt {
+    /// Returns the value as an SSH `mpint`: a 32-bit big-endian length, then the minimal
+    /// big-endian two's complement bytes. Zero has no bytes. Returns `None` beyond
+    /// `u32::MAX` bytes.
+    pub fn to_ssh_mpint(&self) -> Option<Vec<u8>> {
+        let bytes = if self.is_zero() {
+            Vec::new()
+        } else {
+            self.to_signed_bytes_be()
+        };
+
+        let length = u32::try_from(bytes.len()).ok()?;
+
+        let mut result = length.to_be_bytes().to_vec();
+        result.extend_from_slice(&bytes);
+
+        Some(result)
+    }
+
+    /// Decode an SSH `mpint` from the start of `data`, accepting redundant sign bytes.
+    /// Returns the value and the number of bytes read.
+    pub fn from_ssh_mpint(data: &[u8]) -> Result<(Self, usize), DecodeError> {
+        decode_ssh_mpint(data, false)
+    }
+
+    /// Decode an SSH `mpint` from the start of `data`, rejecting redundant sign bytes and
+    /// zero written with any byte. Returns the value and the number of bytes read.
+    pub fn from_ssh_mpint_strict(data: &[u8]) -> Result<(Self, usize), DecodeError> {
+        decode_ssh_mpint(data, true)
+    }
+
+    /// Returns the magnitude as an OpenPGP MPI: a 16-bit big-endian bit count, then the
+    /// big-endian bytes. The sign is dropped. Returns `None` beyond 65535 bits.
+    pub fn to_pgp_mpi(&self) -> Option<Vec<u8>> {
+        let bits = u16::try_from(self.bit_length()).ok()?;
+
+        let mut result = bits.to_be_bytes().to_vec();
+
+        if bits > 0 {
+            result.extend_from_slice(&self.to_bytes_be());
+        }
+
+        Some(result)
+    }
+
+    /// Decode an OpenPGP MPI from the start of `data`, whatever its bit count says about the
+    /// top byte. Returns the value and the number of bytes read.
+    pub fn from_pgp_mpi(data: &[u8]) -> Result<(Self, usize), DecodeError> {
+        decode_pgp_mpi(data, false)
+    }
+
+    /// Decode an OpenPGP MPI from the start of `data`, rejecting a bit count other than the
+    /// exact bit length of the value. Returns the value and the number of bytes read.
+    pub fn from_pgp_mpi_strict(data: &[u8]) -> Result<(Self, usize), DecodeError> {
+        decode_pgp_mpi(data, true)
+    }
+}
+
+/// Decode an SSH `mpint`, checking that it is the minimal encoding if `strict`.
+fn decode_ssh_mpint(data: &[u8], strict: bool) -> Result<(BigInt, usize), DecodeError> {
+    let header = decode::take(data, 0, 4)?;
+    let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+
+    let bytes = decode::take(data, 4, length)?;
+
+    if strict && (bytes == [0] || !decode::is_minimal_twos_complement(bytes)) {
+        return Err(DecodeError::new(DecodeErrorKind::NonMinimal, 4));
+    }
+
+    Ok((BigInt::from_signed_bytes_be(bytes), 4 + length))
+}
+
+/// Decode an OpenPGP MPI, checking that the bit count is exact if `strict`.
+fn decode_pgp_mpi(data: &[u8], strict: bool) -> Result<(BigInt, usize), DecodeError> {
+    let header = decode::take(data, 0, 2)?;
+    let bits = u16::from_be_bytes([header[0], header[1]]) as usize;
+    let length = bits.div_ceil(8);
+
+    let value = BigInt::from_bytes_be(Sign::Plus, decode::take(data, 2, length)?);
+
+    if strict {
+        match value.bit_length().cmp(&bits) {
+            Ordering::Less => {
+                return Err(DecodeError::new(DecodeErrorKind::NonMinimal, 0));
+            }
+            Ordering::Greater => {
+                return Err(DecodeError::new(DecodeErrorKind::InvalidLength, 0));
+            }
+            Ordering::Equal => {}
+        }
+    }
+
+    Ok((value, 2 + length))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigInt, DecodeErrorKind};
+
+    mod ssh {
+        use super::*;
+
+        #[test]
+        fn should_encode_the_examples_of_rfc_4251() {
+            // When / Then
+            assert_eq!(BigInt::from(0u8).to_ssh_mpint(), Some(vec![0, 0, 0, 0]));
+            assert_eq!(
+                BigInt::from(0x09A3_78F9_B2E3_32A7u64).to_ssh_mpint(),
+                Some(vec![
+                    0, 0, 0, 8, 0x09, 0xA3, 0x78, 0xF9, 0xB2, 0xE3, 0x32, 0xA7
+                ])
+            );
+            assert_eq!(
+                BigInt::from(0x80u8).to_ssh_mpint(),
+                Some(vec![0, 0, 0, 2, 0x00, 0x80])
+            );
+            assert_eq!(
+                BigInt::from(-0x1234i16).to_ssh_mpint(),
+                Some(vec![0, 0, 0, 2, 0xED, 0xCC])
+            );
+            assert_eq!(
+                BigInt::from(-0xDEAD_BEEFi64).to_ssh_mpint(),
+                Some(vec![0, 0, 0, 5, 0xFF, 0x21, 0x52, 0x41, 0x11])
+            );
+        }
+
+        #[test]
+        fn should_round_trip() {
+            for value in [0i128, 1, -1, 128, -129, i128::MIN, i128::MAX] {
+                // Given
+                let value = BigInt::from(value);
+
+                // When
+                let encoded = value.to_ssh_mpint().unwrap();
+
+                // Then
+                assert_eq!(
+                    BigInt::from_ssh_mpint_strict(&encoded),
+                    Ok((value, encoded.len()))
+                );
+            }
+        }
+
+        #[test]
+        fn should_reject_redundant_bytes_when_strict() {
+            for (data, expected) in [
+                (&[0, 0, 0, 1, 0x00][..], 0),
+                (&[0, 0, 0, 2, 0x00, 0x7F], 127),
+                (&[0, 0, 0, 2, 0xFF, 0x80], -128),
+            ] {
+                // When
+                let strict = BigInt::from_ssh_mpint_strict(data).unwrap_err();
+                let lenient = BigInt::from_ssh_mpint(data);
+
+                // Then
+                assert_eq!(strict.kind(), DecodeErrorKind::NonMinimal);
+                assert_eq!(lenient, Ok((BigInt::from(expected), data.len())));
+            }
+        }
+
+        #[test]
+        fn should_reject_a_truncated_mpint() {
+            // When / Then
+            assert_eq!(
+                BigInt::from_ssh_mpint(&[0, 0, 0, 3, 0x01, 0x02]).map_err(|error| error.kind()),
+                Err(DecodeErrorKind::Truncated)
+            );
+            assert_eq!(
+                BigInt::from_ssh_mpint(&[0, 0]).map_err(|error| error.kind()),
+                Err(DecodeErrorKind::Truncated)
+            );
+        }
+    }
+
+    mod pgp {
+        use super::*;
+
+        #[test]
+        fn should_count_the_bits() {
+            // When / Then
+            assert_eq!(BigInt::from(0u8).to_pgp_mpi(), Some(vec![0, 0]));
+            assert_eq!(BigInt::from(1u8).to_pgp_mpi(), Some(vec![0, 1, 0x01]));
+            assert_eq!(
+                BigInt::from(511u16).to_pgp_mpi(),
+                Some(vec![0, 9, 0x01, 0xFF])
+            );
+            assert_eq!((BigInt::from(1u8) << 65535).to_pgp_mpi(), None);
+        }
+
+        #[test]
+        fn should_round_trip() {
+            // Given
+            let value = (BigInt::from(1u8) << 65534) + 12345u16;
+
+            // When
+            let encoded = value.to_pgp_mpi().unwrap();
+
+            // Then
+            assert_eq!(encoded[..2], [0xFF, 0xFF]);
+            assert_eq!(BigInt::from_pgp_mpi_strict(&encoded), Ok((value, 2 + 8192)));
+        }
+
+        #[test]
+        fn should_reject_an_inexact_bit_count_when_strict() {
+            for (data, kind, expected) in [
+                (&[0, 10, 0x01, 0xFF][..], DecodeErrorKind::NonMinimal, 511),
+                (&[0, 16, 0x00, 0x01], DecodeErrorKind::NonMinimal, 1),
+                (&[0, 8, 0x01], DecodeErrorKind::NonMinimal, 1),
+                (&[0, 1, 0x03], DecodeErrorKind::InvalidLength, 3),
+            ] {
+                // When
+                let strict = BigInt::from_pgp_mpi_strict(data).unwrap_err();
+                let lenient = BigInt::from_pgp_mpi(data);
+
+                // Then
+                assert_eq!(strict.kind(), kind);
+                assert_eq!(lenient, Ok((BigInt::from(expected as u16), data.len())));
+            }
+        }
+
+        #[test]
+        fn should_reject_a_truncated_mpi() {
+            // When
+            let error = BigInt::from_pgp_mpi(&[0, 17, 0x01, 0x00]).unwrap_err();
+
+            // Then
+            assert_eq!(error.kind(), DecodeErrorKind::Truncated);
+            assert_eq!(error.position(), 4);
+        }
+    }
+}
@@ -0,0 +1,597 @@
+//! Arithmetic and bitwise operators.
+//!
+//! Every binary operator is available for `BigInt op BigInt`, `BigInt op &BigInt`,
+//! `&BigInt op BigInt` and `&BigInt op &BigInt`, together with its assign form. Owned operands
+//! are used as the output buffer whenever the operation allows it.
+//!
+//! Division truncates toward zero and the remainder takes the sign of the dividend, like the
+//! primitive integers. Bitwise operators behave as if the values were stored in two's
+//! complement with an infinite sign extension.
+
+use crate::magnitude;
+use crate::BigInt;
+use std::cmp::Ordering;
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign,
+    Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+
+impl BigInt {
+    /// Add a signed magnitude to `self` in place.
+    pub(crate) fn add_signed(&mut self, data: &[u8], sign: bool) {
+        if self.sign == sign || self.is_zero() {
+            magnitude::add_assign(&mut self.data, data);
+            self.sign = sign;
+        } else {
+            match magnitude::compare(&self.data, data) {
+                Ordering::Less => {
+                    magnitude::reverse_sub_assign(data, &mut self.data);
+                    self.sign = sign;
+                }
+                _ => magnitude::sub_assign(&mut self.data, data),
+            }
+        }
+
+        self.normalize();
+    }
+
+    /// Returns the value in two's complement, little-endian, sign-extended to `length` bytes.
+    /// `length` must leave room for the sign bit.
+    pub(crate) fn to_twos_complement(&self, length: usize) -> Vec<u8> {
+        let mut bytes = magnitude::significant(&self.data).to_vec();
+        bytes.resize(length, 0);
+
+        if self.sign {
+            negate_twos_complement(&mut bytes);
+        }
+
+        bytes
+    }
+
+    /// Create a new `BigInt` from a little-endian two's complement byte array.
+    pub(crate) fn from_twos_complement(mut bytes: Vec<u8>) -> BigInt {
+        let negative = bytes.last().is_some_and(|&byte| byte & 0x80 != 0);
+
+        if negative {
+            negate_twos_complement(&mut bytes);
+        }
+
+        BigInt::from_magnitude(bytes, negative)
+    }
+
+    /// Apply a bitwise operation byte by byte in two's complement.
+    fn bitwise(&mut self, right: &BigInt, operation: impl Fn(u8, u8) -> u8) {
+        let length = self.data.len().max(right.data.len()) + 1;
+
+        let left = self.to_twos_complement(length);
+        let right = right.to_twos_complement(length);
+
+        let result = left
+            .into_iter()
+            .zip(right)
+            .map(|(left, right)| operation(left, right))
+            .collect();
+
+        *self = BigInt::from_twos_complement(result);
+    }
+}
+
+/// Negate a two's complement value in place.
+fn negate_twos_complement(bytes: &mut [u8]) {
+    let mut carry = true;
+
+    for byte in bytes.iter_mut() {
+        let (value, overflow) = (!*byte).overflowing_add(carry as u8);
+
+        *byte = value;
+        carry = overflow;
+    }
+}
+
+impl AddAssign<&BigInt> for BigInt {
+    fn add_assign(&mut self, right: &BigInt) {
+        self.add_signed(&right.data, right.sign);
+    }
+}
+
+impl SubAssign<&BigInt> for BigInt {
+    fn sub_assign(&mut self, right: &BigInt) {
+        self.add_signed(&right.data, !right.sign);
+    }
+}
+
+impl BitAndAssign<&BigInt> for BigInt {
+    fn bitand_assign(&mut self, right: &BigInt) {
+        if self.sign || right.sign {
+            return self.bitwise(right, |left, right| left & right);
+        }
+
+        self.data.truncate(right.data.len());
+
+        for (left, right) in self.data.iter_mut().zip(&right.data) {
+            *left &= right;
+        }
+
+        self.normalize();
+    }
+}
+
+impl BitOrAssign<&BigInt> for BigInt {
+    fn bitor_assign(&mut self, right: &BigInt) {
+        if self.sign || right.sign {
+            return self.bitwise(right, |left, right| left | right);
+        }
+
+        if self.data.len() < right.data.len() {
+            self.data.resize(right.data.len(), 0);
+        }
+
+        for (left, right) in self.data.iter_mut().zip(&right.data) {
+            *left |= right;
+        }
+
+        self.normalize();
+    }
+}
+
+impl BitXorAssign<&BigInt> for BigInt {
+    fn bitxor_assign(&mut self, right: &BigInt) {
+        if self.sign || right.sign {
+            return self.bitwise(right, |left, right| left ^ right);
+        }
+
+        if self.data.len() < right.data.len() {
+            self.data.resize(right.data.len(), 0);
+        }
+
+        for (left, right) in self.data.iter_mut().zip(&right.data) {
+            *left ^= right;
+        }
+
+        self.normalize();
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, right: &BigInt) -> BigInt {
+        BigInt::from_magnitude(
+            magnitude::mul(&self.data, &right.data),
+            self.sign != right.sign,
+        )
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, right: &BigInt) -> BigInt {
+        let (quotient, _) = magnitude::div_rem(&self.data, &right.data);
+
+        BigInt::from_magnitude(quotient, self.sign != right.sign)
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, right: &BigInt) -> BigInt {
+        let (_, remainder) = magnitude::div_rem(&self.data, &right.data);
+
+        BigInt::from_magnitude(remainder, self.sign)
+    }
+}
+
+/// Derive the binary operators from an in-place `OpAssign<&BigInt>` implementation.
+///
+/// `commutative` operators reuse the buffer of the right operand in `&BigInt op BigInt`.
+macro_rules! forward_from_assign {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, commutative) => {
+        forward_from_assign!($trait, $method, $assign_trait, $assign_method);
+
+        impl $trait<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, right: BigInt) -> BigInt {
+                right.$method(self)
+            }
+        }
+    };
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl $assign_trait<BigInt> for BigInt {
+            fn $assign_method(&mut self, right: BigInt) {
+                self.$assign_method(&right);
+            }
+        }
+
+        impl $trait<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(mut self, right: &BigInt) -> BigInt {
+                self.$assign_method(right);
+                self
+            }
+        }
+
+        impl $trait<BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, right: BigInt) -> BigInt {
+                self.$method(&right)
+            }
+        }
+
+        impl $trait<&BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, right: &BigInt) -> BigInt {
+                self.clone().$method(right)
+            }
+        }
+    };
+}
+
+/// Derive the owned and assign forms from an `&BigInt op &BigInt` implementation, for the
+/// operators that always build a new buffer.
+macro_rules! forward_from_references {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl $trait<BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, right: BigInt) -> BigInt {
+                (&self).$method(&right)
+            }
+        }
+
+        impl $trait<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, right: &BigInt) -> BigInt {
+                (&self).$method(right)
+            }
+        }
+
+        impl $trait<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, right: BigInt) -> BigInt {
+                self.$method(&right)
+            }
+        }
+
+        impl $assign_trait<&BigInt> for BigInt {
+            fn $assign_method(&mut self, right: &BigInt) {
+                *self = (&*self).$method(right);
+            }
+        }
+
+        impl $assign_trait<BigInt> for BigInt {
+            fn $assign_method(&mut self, right: BigInt) {
+                *self = (&*self).$method(&right);
+            }
+        }
+    };
+}
+
+forward_from_assign!(Add, add, AddAssign, add_assign, commutative);
+forward_from_assign!(Sub, sub, SubAssign, sub_assign);
+forward_from_assign!(BitAnd, bitand, BitAndAssign, bitand_assign, commutative);
+forward_from_assign!(BitOr, bitor, BitOrAssign, bitor_assign, commutative);
+forward_from_assign!(BitXor, bitxor, BitXorAssign, bitxor_assign, commutative);
+
+forward_from_references!(Mul, mul, MulAssign, mul_assign);
+forward_from_references!(Div, div, DivAssign, div_assign);
+forward_from_references!(Rem, rem, RemAssign, rem_assign);
+
+impl Sub<BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, right: BigInt) -> BigInt {
+        // a - b = -(b - a), which reuses the buffer of `right`.
+        -(right - self)
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(mut self) -> BigInt {
+        self.sign = !self.sign;
+        self.normalize();
+        self
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Not for BigInt {
+    type Output = BigInt;
+
+    fn not(self) -> BigInt {
+        // !a = -a - 1
+        -self - BigInt::from_bytes(vec![1], false)
+    }
+}
+
+impl Not for &BigInt {
+    type Output = BigInt;
+
+    fn not(self) -> BigInt {
+        !self.clone()
+    }
+}
+
+impl ShlAssign<usize> for BigInt {
+    fn shl_assign(&mut self, bits: usize) {
+        self.data = magnitude::shl(&self.data, bits);
+        self.normalize();
+    }
+}
+
+impl ShrAssign<usize> for BigInt {
+    fn shr_assign(&mut self, bits: usize) {
+        // Negative values round toward negative infinity, like an arithmetic shift.
+        let round_down = self.sign && magnitude::has_low_bits(&self.data, bits);
+
+        self.data = magnitude::shr(&self.data, bits);
+
+        if round_down {
+            magnitude::add_assign(&mut self.data, &[1]);
+        }
+
+        self.normalize();
+    }
+}
+
+impl Shl<usize> for BigInt {
+    type Output = BigInt;
+
+    fn shl(mut self, bits: usize) -> BigInt {
+        self <<= bits;
+        self
+    }
+}
+
+impl Shl<usize> for &BigInt {
+    type Output = BigInt;
+
+    fn shl(self, bits: usize) -> BigInt {
+        BigInt::from_magnitude(magnitude::shl(&self.data, bits), self.sign)
+    }
+}
+
+impl Shr<usize> for BigInt {
+    type Output = BigInt;
+
+    fn shr(mut self, bits: usize) -> BigInt {
+        self >>= bits;
+        self
+    }
+}
+
+impl Shr<usize> for &BigInt {
+    type Output = BigInt;
+
+    fn shr(self, bits: usize) -> BigInt {
+        self.clone() >> bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::number;
+    use crate::Base;
+
+    mod add {
+        use super::*;
+
+        #[test]
+        fn should_add_references() {
+            // Given
+            let a = number("123456789012345678901234567890");
+            let b = number("987654321098765432109876543210");
+
+            // When
+            let result = &a + &b;
+
+            // Then
+            assert_eq!(result, number("1111111110111111111011111111100"));
+        }
+
+        #[test]
+        fn should_add_mixed_owned_and_borrowed_operands() {
+            // Given
+            let a = number("1000");
+            let b = number("-1");
+
+            // When
+            let owned_left = a.clone() + &b;
+            let owned_right = &a + b.clone();
+
+            // Then
+            assert_eq!(owned_left, number("999"));
+            assert_eq!(owned_right, number("999"));
+        }
+
+        #[test]
+        fn should_add_numbers_with_different_signs() {
+            // Given
+            let a = number("-300");
+            let b = number("200");
+
+            // When
+            let result = a + b;
+
+            // Then
+            assert_eq!(result, number("-100"));
+        }
+
+        #[test]
+        fn should_add_assign() {
+            // Given
+            let mut a = number("255");
+
+            // When
+            a += &number("1");
+            a += number("-256");
+
+            // Then
+            assert_eq!(a, number("0"));
+        }
+    }
+
+    mod sub {
+        use super::*;
+
+        #[test]
+        fn should_subtract_to_a_negative_number() {
+            // Given
+            let a = number("5");
+            let b = number("12");
+
+            // When
+            let result = &a - &b;
+
+            // Then
+            assert_eq!(result, number("-7"));
+        }
+
+        #[test]
+        fn should_subtract_a_borrowed_operand_from_an_owned_one() {
+            // Given
+            let a = number("18446744073709551616");
+            let b = number("1");
+
+            // When
+            let result = &a - b;
+
+            // Then
+            assert_eq!(result, number("18446744073709551615"));
+        }
+    }
+
+    mod mul {
+        use super::*;
+
+        #[test]
+        fn should_multiply_with_signs() {
+            // Given
+            let a = number("-12345678901234567890");
+            let b = number("98765432109876543210");
+
+            // When
+            let result = &a * &b;
+
+            // Then
+            assert_eq!(result, number("-1219326311370217952237463801111263526900"));
+        }
+
+        #[test]
+        fn should_multiply_by_zero_without_a_negative_zero() {
+            // Given
+            let a = number("-5");
+            let b = number("0");
+
+            // When
+            let result = a * b;
+
+            // Then
+            assert_eq!(result.to_string(Base::Decimal), "0");
+        }
+    }
+
+    mod div_rem {
+        use super::*;
+
+        #[test]
+        fn should_truncate_toward_zero() {
+            // Given
+            let a = number("-7");
+            let b = number("2");
+
+            // When
+            let quotient = &a / &b;
+            let remainder = &a % &b;
+
+            // Then
+            assert_eq!(quotient, number("-3"));
+            assert_eq!(remainder, number("-1"));
+        }
+
+        #[test]
+        fn should_divide_multi_word_numbers() {
+            // Given
+            let a = number("1219326311370217952237463801111263526900");
+            let b = number("98765432109876543210");
+
+            // When
+            let quotient = a.clone() / &b;
+            let remainder = a % b;
+
+            // Then
+            assert_eq!(quotient, number("12345678901234567890"));
+            assert_eq!(remainder, number("0"));
+        }
+
+        #[test]
+        #[should_panic(expected = "attempt to divide by zero")]
+        fn should_panic_on_division_by_zero() {
+            // Given
+            let a = number("1");
+            let b = number("0");
+
+            // When
+            let _ = a / b;
+        }
+    }
+
+    mod bitwise {
+        use super::*;
+
+        #[test]
+        fn should_apply_bitwise_operators_to_positive_numbers() {
+            // Given
+            let a = number("12"); // 0b1100
+            let b = number("10"); // 0b1010
+
+            // When / Then
+            assert_eq!(&a & &b, number("8"));
+            assert_eq!(&a | &b, number("14"));
+            assert_eq!(&a ^ &b, number("6"));
+        }
+
+        #[test]
+        fn should_apply_bitwise_operators_in_twos_complement() {
+            // Given
+            let a = number("-12");
+            let b = number("10");
+
+            // When / Then
+            assert_eq!(&a & &b, number("0"));
+            assert_eq!(&a | &b, number("-2"));
+            assert_eq!(&a ^ &b, number("-2"));
+            assert_eq!(!&b, number("-11"));
+            assert_eq!(!a, number("11"));
+        }
+
+        #[test]
+        fn should_shift_left_and_right() {
+            // Given
+            let a = number("1");
+            let b = number("-5");
+
+            // When / Then
+            assert_eq!(&a << 100, number("1267650600228229401496703205376"));
+            assert_eq!((a << 100) >> 99, number("2"));
+            assert_eq!(&b >> 1, number("-3"));
+            assert_eq!(b >> 64, number("-1"));
+        }
+    }
+}
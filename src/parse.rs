@@ -0,0 +1,471 @@
+//! Parsing of `BigInt` values that reports malformed input instead of panicking.
+
+use crate::magnitude;
+use crate::radix;
+use crate::{Base, BigInt};
+
+/// What went wrong while parsing a `BigInt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The input has no digits.
+    Empty,
+    /// A character is not a digit in any radix.
+    InvalidDigit,
+    /// A character is a digit, but not in the requested radix.
+    DigitOutOfRange,
+    /// A sign appears somewhere else than at the start of the number.
+    MisplacedSign,
+    /// A number with a fraction or an exponent does not have an integral value.
+    NotAnInteger,
+    /// The exponent of a number is above the accepted maximum.
+    ExponentOutOfRange,
+}
+
+/// The error returned when a string cannot be parsed as a `BigInt`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError {
+    /// What went wrong.
+    kind: ParseErrorKind,
+
+    /// Byte offset in the input where the problem was found.
+    position: usize,
+}
+
+impl ParseBigIntError {
+    pub(crate) fn new(kind: ParseErrorKind, position: usize) -> Self {
+        ParseBigIntError { kind, position }
+    }
+
+    /// Returns what went wrong.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte offset in the input where the problem was found.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl std::fmt::Display for ParseBigIntError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let description = match self.kind {
+            ParseErrorKind::Empty => "no digits to parse",
+            ParseErrorKind::InvalidDigit => "invalid digit",
+            ParseErrorKind::DigitOutOfRange => "digit out of range for the radix",
+            ParseErrorKind::MisplacedSign => "misplaced sign",
+            ParseErrorKind::NotAnInteger => "number not an integer",
+            ParseErrorKind::ExponentOutOfRange => "exponent out of range",
+        };
+
+        write!(formatter, "{} at byte {}", description, self.position)
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+/// Options for [`BigInt::from_literal`].
+///
+/// By default surrounding whitespace is an error and underscores may come anywhere among the
+/// digits. Signs are not an option: a single `+` or `-` may come before the prefix, and any
+/// other sign is a [`ParseErrorKind::MisplacedSign`] error.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LiteralOptions {
+    /// Ignore whitespace around the literal.
+    trim_whitespace: bool,
+
+    /// Reject a leading underscore in an unprefixed literal.
+    strict_underscores: bool,
+}
+
+impl LiteralOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ignore whitespace around the literal.
+    pub fn trim_whitespace(mut self, trim_whitespace: bool) -> Self {
+        self.trim_whitespace = trim_whitespace;
+        self
+    }
+
+    /// Reject a leading underscore in an unprefixed literal, like the Rust grammar, where `_1`
+    /// is an identifier.
+    pub fn strict_underscores(mut self, strict_underscores: bool) -> Self {
+        self.strict_underscores = strict_underscores;
+        self
+    }
+}
+
+/// Returns the radix announced by a `0x`, `0o` or `0b` prefix, if the data starts with one.
+fn literal_prefix(data: &[u8]) -> Option<u8> {
+    match data {
+        [b'0', b'x' | b'X', ..] => Some(16),
+        [b'0', b'o' | b'O', ..] => Some(8),
+        [b'0', b'b' | b'B', ..] => Some(2),
+        _ => None,
+    }
+}
+
+/// Returns the value of a digit character, in any radix up to 36.
+pub(crate) fn digit_value(character: char) -> Option<u8> {
+    match character {
+        '0'..='9' => Some(character as u8 - b'0'),
+        'a'..='z' => Some(character as u8 - b'a' + 10),
+        'A'..='Z' => Some(character as u8 - b'A' + 10),
+        _ => None,
+    }
+}
+
+impl BigInt {
+    /// Create a new `BigInt` from a string in the provided base, reporting malformed input as
+    /// an error. The digits may be preceded by a single `+` or `-`.
+    pub fn from_str_radix(data: &str, base: Base) -> Result<Self, ParseBigIntError> {
+        let radix = base.to_radix();
+
+        let (negative, start) = match data.as_bytes().first() {
+            Some(b'-') => (true, 1),
+            Some(b'+') => (false, 1),
+            _ => (false, 0),
+        };
+
+        if data.len() == start {
+            return Err(ParseBigIntError::new(ParseErrorKind::Empty, start));
+        }
+
+        let mut digits = Vec::with_capacity(data.len() - start);
+
+        for (offset, character) in data[start..].char_indices() {
+            let position = start + offset;
+
+            match digit_value(character) {
+                Some(value) if value < radix => digits.push(value),
+                Some(_) => {
+                    return Err(ParseBigIntError::new(
+                        ParseErrorKind::DigitOutOfRange,
+                        position,
+                    ))
+                }
+                None if character == '-' || character == '+' => {
+                    return Err(ParseBigIntError::new(
+                        ParseErrorKind::MisplacedSign,
+                        position,
+                    ))
+                }
+                None => {
+                    return Err(ParseBigIntError::new(
+                        ParseErrorKind::InvalidDigit,
+                        position,
+                    ))
+                }
+            }
+        }
+
+        Ok(BigInt::from_digits(&digits, radix, negative))
+    }
+
+    /// Create a new `BigInt` from a Rust-style integer literal such as `-0x_FF_FF`, `0b1010`
+    /// or `1_000_000`.
+    ///
+    /// The base is picked from the `0x`, `0o` or `0b` prefix (in either case) and defaults to
+    /// decimal. Underscores may separate the digits. See [`LiteralOptions`] for the handling of
+    /// signs and whitespace.
+    pub fn from_literal(data: &str, options: LiteralOptions) -> Result<Self, ParseBigIntError> {
+        let bytes = data.as_bytes();
+
+        let (mut position, end) = if options.trim_whitespace {
+            let start = data.len() - data.trim_start().len();
+
+            (start, data.trim_end().len().max(start))
+        } else {
+            (0, data.len())
+        };
+
+        // A single sign, then the prefix. Any other sign is reported with the digits.
+        let negative = position < end && bytes[position] == b'-';
+
+        if position < end && matches!(bytes[position], b'-' | b'+') {
+            position += 1;
+        }
+
+        let radix = literal_prefix(&bytes[position..end]);
+
+        if radix.is_some() {
+            position += 2;
+        }
+
+        let prefixed = radix.is_some();
+        let radix = radix.unwrap_or(10);
+
+        let mut digits = Vec::with_capacity(end - position);
+
+        for (offset, character) in data[position..end].char_indices() {
+            let position = position + offset;
+
+            match digit_value(character) {
+                Some(value) if value < radix => digits.push(value),
+                Some(_) => {
+                    return Err(ParseBigIntError::new(
+                        ParseErrorKind::DigitOutOfRange,
+                        position,
+                    ))
+                }
+                None if character == '_' => {
+                    if options.strict_underscores && !prefixed && digits.is_empty() {
+                        return Err(ParseBigIntError::new(
+                            ParseErrorKind::InvalidDigit,
+                            position,
+                        ));
+                    }
+                }
+                None if character == '-' || character == '+' => {
+                    return Err(ParseBigIntError::new(
+                        ParseErrorKind::MisplacedSign,
+                        position,
+                    ))
+                }
+                None => {
+                    return Err(ParseBigIntError::new(
+                        ParseErrorKind::InvalidDigit,
+                        position,
+                    ))
+                }
+            }
+        }
+
+        if digits.is_empty() {
+            return Err(ParseBigIntError::new(ParseErrorKind::Empty, end));
+        }
+
+        Ok(BigInt::from_digits(&digits, radix, negative))
+    }
+
+    /// Create a new `BigInt` from digit values, most significant first.
+    pub(crate) fn from_digits(digits: &[u8], radix: u8, negative: bool) -> Self {
+        if radix.is_power_of_two() || digits.len() > radix::SMALL_LENGTH {
+            return BigInt::from_magnitude(radix::from_digits(digits, radix), negative);
+        }
+
+        // Fold as many digits as fit in a word before touching the big number.
+        let radix = radix as u64;
+        let (_, chunk_length) = magnitude::largest_power(radix);
+
+        let mut result = BigInt::from(0u8);
+        let first_length = match digits.len() % chunk_length {
+            0 => chunk_length,
+            length => length,
+        };
+
+        let (first, rest) = digits.split_at(first_length.min(digits.len()));
+
+        for chunk in std::iter::once(first).chain(rest.chunks(chunk_length)) {
+            let value = chunk
+                .iter()
+                .fold(0u64, |value, &digit| value * radix + digit as u64);
+
+            result = result.multiply_by_small(radix.pow(chunk.len() as u32)) + value;
+        }
+
+        BigInt::from_magnitude(result.data, negative)
+    }
+}
+
+impl std::str::FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// Parse a decimal `BigInt`.
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        BigInt::from_str_radix(data, Base::Decimal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Base, BigInt, ParseErrorKind};
+
+    mod from_str_radix {
+        use super::*;
+
+        #[test]
+        fn should_parse_signed_numbers() {
+            // When
+            let negative = BigInt::from_str_radix("-123456789012345678901234567890", Base::Decimal);
+            let positive = BigInt::from_str_radix("+ff", Base::Hexadecimal);
+
+            // Then
+            assert_eq!(
+                negative.unwrap().to_string(Base::Decimal),
+                "-123456789012345678901234567890"
+            );
+            assert_eq!(positive.unwrap(), BigInt::from(255u8));
+        }
+
+        #[test]
+        fn should_parse_long_binary_numbers() {
+            // Given
+            let data = "1".repeat(130);
+
+            // When
+            let result = BigInt::from_str_radix(&data, Base::Binary).unwrap();
+
+            // Then
+            assert_eq!(result, (BigInt::from(1u8) << 130) - 1u8);
+        }
+
+        #[test]
+        fn should_reject_empty_input() {
+            // When
+            let empty = BigInt::from_str_radix("", Base::Decimal).unwrap_err();
+            let sign_only = BigInt::from_str_radix("-", Base::Decimal).unwrap_err();
+
+            // Then
+            assert_eq!((empty.kind(), empty.position()), (ParseErrorKind::Empty, 0));
+            assert_eq!(
+                (sign_only.kind(), sign_only.position()),
+                (ParseErrorKind::Empty, 1)
+            );
+        }
+
+        #[test]
+        fn should_report_the_offset_of_an_invalid_digit() {
+            // When
+            let error = BigInt::from_str_radix("12 34", Base::Decimal).unwrap_err();
+
+            // Then
+            assert_eq!(error.kind(), ParseErrorKind::InvalidDigit);
+            assert_eq!(error.position(), 2);
+            assert_eq!(error.to_string(), "invalid digit at byte 2");
+        }
+
+        #[test]
+        fn should_report_a_digit_out_of_range() {
+            // When
+            let error = BigInt::from_str_radix("1012", Base::Binary).unwrap_err();
+
+            // Then
+            assert_eq!(error.kind(), ParseErrorKind::DigitOutOfRange);
+            assert_eq!(error.position(), 3);
+        }
+
+        #[test]
+        fn should_parse_any_radix_up_to_36() {
+            // When
+            let result = BigInt::from_str_radix("-z1", Base::from_radix(36).unwrap());
+
+            // Then
+            assert_eq!(result, Ok(BigInt::from(-1261i16)));
+        }
+
+        #[test]
+        fn should_report_a_misplaced_sign() {
+            // When
+            let error = BigInt::from_str_radix("1-2", Base::Decimal).unwrap_err();
+
+            // Then
+            assert_eq!(error.kind(), ParseErrorKind::MisplacedSign);
+            assert_eq!(error.position(), 1);
+        }
+    }
+
+    mod from_literal {
+        use super::*;
+        use crate::LiteralOptions;
+
+        #[test]
+        fn should_pick_the_base_from_the_prefix() {
+            // Given
+            let options = LiteralOptions::new();
+
+            // When / Then
+            let parse = |data| BigInt::from_literal(data, options).unwrap();
+
+            assert_eq!(parse("-0x_FF_FF"), BigInt::from(-65535i32));
+            assert_eq!(parse("0o777"), BigInt::from(511u16));
+            assert_eq!(parse("0B1010"), BigInt::from(10u8));
+            assert_eq!(parse("+1_000_000"), BigInt::from(1_000_000u32));
+            assert_eq!(parse("0"), BigInt::from(0u8));
+        }
+
+        #[test]
+        fn should_trim_whitespace_only_when_asked() {
+            // Given
+            let data = "  \t0xff\n";
+
+            // When
+            let trimmed = BigInt::from_literal(data, LiteralOptions::new().trim_whitespace(true));
+            let untrimmed = BigInt::from_literal(data, LiteralOptions::new()).unwrap_err();
+
+            // Then
+            assert_eq!(trimmed, Ok(BigInt::from(255u8)));
+            assert_eq!(untrimmed.kind(), ParseErrorKind::InvalidDigit);
+            assert_eq!(untrimmed.position(), 0);
+        }
+
+        #[test]
+        fn should_reject_misplaced_signs_whatever_the_options() {
+            for options in [
+                LiteralOptions::new(),
+                LiteralOptions::new().strict_underscores(true),
+                LiteralOptions::new().trim_whitespace(true),
+            ] {
+                // Given
+                let error = |data| {
+                    let error = BigInt::from_literal(data, options).unwrap_err();
+
+                    (error.kind(), error.position())
+                };
+
+                // When / Then
+                assert_eq!(error("--5"), (ParseErrorKind::MisplacedSign, 1));
+                assert_eq!(error("+-+-5"), (ParseErrorKind::MisplacedSign, 1));
+                assert_eq!(error("0x-10"), (ParseErrorKind::MisplacedSign, 2));
+                assert_eq!(
+                    BigInt::from_literal("-0x_10", options),
+                    Ok(BigInt::from(-16i8))
+                );
+            }
+        }
+
+        #[test]
+        fn should_reject_a_sign_after_the_digits() {
+            // When
+            let error = BigInt::from_literal("1-2", LiteralOptions::new()).unwrap_err();
+
+            // Then
+            assert_eq!(error.kind(), ParseErrorKind::MisplacedSign);
+            assert_eq!(error.position(), 1);
+        }
+
+        #[test]
+        fn should_reject_literals_without_digits() {
+            // When
+            let prefix_only = BigInt::from_literal("0x__", LiteralOptions::new()).unwrap_err();
+            let leading_underscore =
+                BigInt::from_literal("_1", LiteralOptions::new().strict_underscores(true))
+                    .unwrap_err();
+
+            // Then
+            assert_eq!(prefix_only.kind(), ParseErrorKind::Empty);
+            assert_eq!(prefix_only.position(), 4);
+            assert_eq!(leading_underscore.kind(), ParseErrorKind::InvalidDigit);
+            assert_eq!(
+                BigInt::from_literal("_1", LiteralOptions::new()),
+                Ok(BigInt::from(1u8))
+            );
+        }
+    }
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn should_parse_decimal_numbers() {
+            // When
+            let result: Result<BigInt, _> = "-42".parse();
+
+            // Then
+            assert_eq!(result, Ok(BigInt::from(-42i8)));
+        }
+    }
+}
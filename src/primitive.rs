@@ -0,0 +1,358 @@
+//! Arithmetic and bitwise operators between `BigInt` and the primitive integers.
+//!
+//! `BigInt op primitive`, `primitive op BigInt` (owned or borrowed) and the assign forms are
+//! implemented for every primitive integer type. Addition, subtraction, multiplication and
+//! division by values that fit in a word run single-word routines in place, without building
+//! a `BigInt` for the primitive operand.
+
+use crate::magnitude;
+use crate::BigInt;
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign,
+    Mul, MulAssign, Rem, RemAssign, Sub, SubAssign,
+};
+
+/// A primitive integer, split into its magnitude and its sign.
+pub(crate) trait Primitive: Copy {
+    /// Returns the pair (magnitude, sign), where `sign` is `true` for negative values.
+    fn split(self) -> (u128, bool);
+}
+
+macro_rules! impl_primitive {
+    (unsigned: $($primitive:ty),*) => {
+        $(
+            impl Primitive for $primitive {
+                fn split(self) -> (u128, bool) {
+                    (self as u128, false)
+                }
+            }
+        )*
+    };
+    (signed: $($primitive:ty),*) => {
+        $(
+            impl Primitive for $primitive {
+                fn split(self) -> (u128, bool) {
+                    (self.unsigned_abs() as u128, self < 0)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive!(unsigned: u8, u16, u32, u64, u128, usize);
+impl_primitive!(signed: i8, i16, i32, i64, i128, isize);
+
+impl BigInt {
+    /// Create a new `BigInt` from a primitive integer.
+    pub(crate) fn from_primitive(value: impl Primitive) -> BigInt {
+        let (value, sign) = value.split();
+
+        BigInt::from_magnitude(value.to_le_bytes().to_vec(), sign)
+    }
+
+    /// Add a primitive integer to `self` in place.
+    fn add_primitive(&mut self, value: impl Primitive, negate: bool) {
+        let (value, sign) = value.split();
+
+        self.add_signed(&value.to_le_bytes(), sign != negate);
+    }
+
+    /// Multiply `self` by a primitive integer in place.
+    fn mul_primitive(&mut self, value: impl Primitive) {
+        let (value, sign) = value.split();
+
+        match u64::try_from(value) {
+            Ok(value) => magnitude::mul_word_assign(&mut self.data, value),
+            Err(_) => self.data = magnitude::mul(&self.data, &value.to_le_bytes()),
+        }
+
+        self.sign ^= sign;
+        self.normalize();
+    }
+
+    /// Divide `self` by a primitive integer in place, keeping either the quotient or the
+    /// remainder.
+    fn div_rem_primitive(&mut self, value: impl Primitive, keep_remainder: bool) {
+        let (value, sign) = value.split();
+
+        let (quotient, remainder) = match u64::try_from(value) {
+            Ok(value) => {
+                let mut quotient = std::mem::take(&mut self.data);
+                let remainder = magnitude::div_word_assign(&mut quotient, value);
+
+                (quotient, remainder.to_le_bytes().to_vec())
+            }
+            Err(_) => magnitude::div_rem(&self.data, &value.to_le_bytes()),
+        };
+
+        if keep_remainder {
+            self.data = remainder;
+        } else {
+            self.data = quotient;
+            self.sign ^= sign;
+        }
+
+        self.normalize();
+    }
+}
+
+/// Implement `BigInt op primitive`, `&BigInt op primitive` and `primitive op BigInt` from the
+/// `OpAssign<primitive>` implementation.
+///
+/// `commutative` operators swap the operands, `subtract` negates the swapped difference and
+/// `promote` turns the primitive into a `BigInt` first.
+macro_rules! forward_primitive_operator {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $order:ident;
+        $($primitive:ty),*) => {
+        $(
+            impl $trait<$primitive> for BigInt {
+                type Output = BigInt;
+
+                fn $method(mut self, right: $primitive) -> BigInt {
+                    self.$assign_method(right);
+                    self
+                }
+            }
+
+            impl $trait<$primitive> for &BigInt {
+                type Output = BigInt;
+
+                fn $method(self, right: $primitive) -> BigInt {
+                    self.clone().$method(right)
+                }
+            }
+
+            impl $trait<BigInt> for $primitive {
+                type Output = BigInt;
+
+                fn $method(self, right: BigInt) -> BigInt {
+                    forward_primitive_operator!(@swap $order, $method, self, right)
+                }
+            }
+
+            impl $trait<&BigInt> for $primitive {
+                type Output = BigInt;
+
+                fn $method(self, right: &BigInt) -> BigInt {
+                    forward_primitive_operator!(@swap $order, $method, self, right)
+                }
+            }
+        )*
+    };
+    (@swap commutative, $method:ident, $left:ident, $right:ident) => {
+        $right.$method($left)
+    };
+    (@swap subtract, $method:ident, $left:ident, $right:ident) => {
+        -($right.$method($left))
+    };
+    (@swap promote, $method:ident, $left:ident, $right:ident) => {
+        BigInt::from_primitive($left).$method($right)
+    };
+}
+
+/// Implement `OpAssign<primitive>` for `BigInt` with the given body.
+macro_rules! impl_primitive_assign {
+    ($assign_trait:ident, $assign_method:ident, |$target:ident, $value:ident| $body:expr;
+        $($primitive:ty),*) => {
+        $(
+            impl $assign_trait<$primitive> for BigInt {
+                fn $assign_method(&mut self, $value: $primitive) {
+                    let $target = self;
+                    $body
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_primitive_operators {
+    ($($primitive:ty),*) => {
+        impl_primitive_assign!(AddAssign, add_assign,
+            |target, value| target.add_primitive(value, false); $($primitive),*);
+        impl_primitive_assign!(SubAssign, sub_assign,
+            |target, value| target.add_primitive(value, true); $($primitive),*);
+        impl_primitive_assign!(MulAssign, mul_assign,
+            |target, value| target.mul_primitive(value); $($primitive),*);
+        impl_primitive_assign!(DivAssign, div_assign,
+            |target, value| target.div_rem_primitive(value, false); $($primitive),*);
+        impl_primitive_assign!(RemAssign, rem_assign,
+            |target, value| target.div_rem_primitive(value, true); $($primitive),*);
+        impl_primitive_assign!(BitAndAssign, bitand_assign,
+            |target, value| *target &= &BigInt::from_primitive(value); $($primitive),*);
+        impl_primitive_assign!(BitOrAssign, bitor_assign,
+            |target, value| *target |= &BigInt::from_primitive(value); $($primitive),*);
+        impl_primitive_assign!(BitXorAssign, bitxor_assign,
+            |target, value| *target ^= &BigInt::from_primitive(value); $($primitive),*);
+
+        forward_primitive_operator!(Add, add, AddAssign, add_assign, commutative; $($primitive),*);
+        forward_primitive_operator!(Sub, sub, SubAssign, sub_assign, subtract; $($primitive),*);
+        forward_primitive_operator!(Mul, mul, MulAssign, mul_assign, commutative; $($primitive),*);
+        forward_primitive_operator!(Div, div, DivAssign, div_assign, promote; $($primitive),*);
+        forward_primitive_operator!(Rem, rem, RemAssign, rem_assign, promote; $($primitive),*);
+        forward_primitive_operator!(BitAnd, bitand, BitAndAssign, bitand_assign, commutative;
+            $($primitive),*);
+        forward_primitive_operator!(BitOr, bitor, BitOrAssign, bitor_assign, commutative;
+            $($primitive),*);
+        forward_primitive_operator!(BitXor, bitxor, BitXorAssign, bitxor_assign, commutative;
+            $($primitive),*);
+    };
+}
+
+impl_primitive_operators!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::number;
+
+    mod add_sub {
+        use super::*;
+
+        #[test]
+        fn should_increment_with_a_primitive() {
+            // Given
+            let a = number("18446744073709551615");
+
+            // When
+            let result = a + 1u8;
+
+            // Then
+            assert_eq!(result, number("18446744073709551616"));
+        }
+
+        #[test]
+        fn should_add_a_negative_primitive() {
+            // Given
+            let a = number("10");
+
+            // When
+            let result = &a + -15i32;
+
+            // Then
+            assert_eq!(result, number("-5"));
+        }
+
+        #[test]
+        fn should_subtract_a_bigint_from_a_primitive() {
+            // Given
+            let a = number("3");
+
+            // When
+            let result = 1u64 - &a;
+
+            // Then
+            assert_eq!(result, number("-2"));
+        }
+
+        #[test]
+        fn should_add_and_subtract_assign_with_extreme_primitives() {
+            // Given
+            let mut a = number("0");
+
+            // When
+            a += i128::MIN;
+            a -= u128::MAX;
+
+            // Then
+            assert_eq!(a, number("-510423550381407695195061911147652317183"));
+        }
+    }
+
+    mod mul {
+        use super::*;
+
+        #[test]
+        fn should_multiply_by_a_single_word() {
+            // Given
+            let a = number("-123456789012345678901234567890");
+
+            // When
+            let result = a * 1_000_000_007u64;
+
+            // Then
+            assert_eq!(result, number("-123456789876543201987654320198641975230"));
+        }
+
+        #[test]
+        fn should_multiply_by_a_double_word() {
+            // Given
+            let a = number("3");
+
+            // When
+            let result = i128::MIN * a;
+
+            // Then
+            assert_eq!(result, number("-510423550381407695195061911147652317184"));
+        }
+    }
+
+    mod div_rem {
+        use super::*;
+
+        #[test]
+        fn should_divide_and_take_the_remainder_by_a_primitive() {
+            // Given
+            let a = number("-1000000000000000000000");
+
+            // When
+            let quotient = &a / 7u8;
+            let remainder = &a % 7u8;
+
+            // Then
+            assert_eq!(quotient, number("-142857142857142857142"));
+            assert_eq!(remainder, number("-6"));
+        }
+
+        #[test]
+        fn should_divide_by_a_negative_primitive() {
+            // Given
+            let mut a = number("100");
+
+            // When
+            a /= -7i64;
+
+            // Then
+            assert_eq!(a, number("-14"));
+        }
+
+        #[test]
+        fn should_divide_a_primitive_by_a_bigint() {
+            // Given
+            let a = number("7");
+
+            // When
+            let quotient = 100u32 / &a;
+            let remainder = 100u32 % a;
+
+            // Then
+            assert_eq!(quotient, number("14"));
+            assert_eq!(remainder, number("2"));
+        }
+
+        #[test]
+        fn should_divide_by_a_large_word() {
+            // Given
+            let a = number("340282366920938463463374607431768211455");
+
+            // When
+            let remainder = a % u64::MAX;
+
+            // Then
+            assert_eq!(remainder, number("0"));
+        }
+    }
+
+    mod bitwise {
+        use super::*;
+
+        #[test]
+        fn should_apply_bitwise_operators_with_primitives() {
+            // Given
+            let a = number("-256");
+
+            // When / Then
+            assert_eq!(&a & 0x1FFu16, number("256"));
+            assert_eq!(&a | 1u8, number("-255"));
+            assert_eq!(-1i8 ^ a, number("255"));
+        }
+    }
+}
@@ -0,0 +1,738 @@
+//! Conversion of magnitudes to and from digits in a radix.
+//!
+//! Power-of-two radices slice the bits directly. The other radices split the number in halves
+//! around precomputed powers of the radix (divide and conquer), so that the cost follows the one of
+//! multiplication and division instead of growing with the square of the length. The powers of
+//! two in those powers are shifts, so only their odd parts are multiplied and divided.
+
+use crate::magnitude;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// Up to this many bytes (or digits) the word-at-a-time conversions in `BigInt` are used.
+pub(crate) const SMALL_LENGTH: usize = 256;
+
+/// Below this many words (or word-sized digit chunks) the divide and conquer recursion stops.
+const RECURSION_THRESHOLD: usize = 32;
+
+/// The powers of the radix that split a number in halves, then the halves in halves, and so
+/// on.
+struct Powers {
+    /// The largest power of the radix that fits in a word.
+    chunk: u64,
+
+    /// Number of digits in a `chunk`.
+    chunk_length: usize,
+
+    /// Exponents of `chunk`, each one half of the previous one, rounded down.
+    exponents: Vec<usize>,
+
+    /// The odd part of `chunk^exponent` for each exponent.
+    values: Vec<Vec<u64>>,
+
+    /// The exponent of two in `chunk^exponent` for each exponent.
+    shifts: Vec<usize>,
+}
+
+impl Powers {
+    /// Compute the powers splitting a number of `chunks` word-sized digit chunks.
+    fn new(radix: u8, chunks: usize) -> Self {
+        let (chunk, chunk_length) = magnitude::largest_power(radix as u64);
+        let odd_chunk = chunk >> chunk.trailing_zeros();
+
+        let mut exponents = Vec::new();
+        let mut exponent = chunks / 2;
+
+        while exponent >= RECURSION_THRESHOLD / 2 {
+            exponents.push(exponent);
+            exponent /= 2;
+        }
+
+        // Each power is the square of the next one, times `chunk` for an odd exponent.
+        let mut values: Vec<Vec<u64>> = Vec::with_capacity(exponents.len());
+
+        for &exponent in exponents.iter().rev() {
+            let mut value = match values.last() {
+                Some(half) => magnitude::mul_words(half, half),
+                None => {
+                    let half = (0..exponent / 2).fold(vec![1u64], |power, _| {
+                        magnitude::mul_words(&power, &[odd_chunk])
+                    });
+
+                    magnitude::mul_words(&half, &half)
+                }
+            };
+
+            if exponent % 2 == 1 {
+                value = magnitude::mul_words(&value, &[odd_chunk]);
+            }
+
+            values.push(value);
+        }
+
+        values.reverse();
+
+        let shifts = exponents
+            .iter()
+            .map(|exponent| exponent * chunk.trailing_zeros() as usize)
+            .collect();
+
+        Powers {
+            chunk,
+            chunk_length,
+            exponents,
+            values,
+            shifts,
+        }
+    }
+
+    /// Divide `words` by the power at `level` and return the pair (quotient, remainder).
+    fn div_rem(&self, words: &[u64], level: usize) -> (Vec<u64>, Vec<u64>) {
+        // With the power `odd * 2^shift`, the remainder is the one of the odd part, shifted,
+        // plus the bits shifted out.
+        let (high, low) = magnitude::split_words_at(words, self.shifts[level]);
+        let (quotient, remainder) = magnitude::div_rem_words(&high, &self.values[level]);
+
+        let mut remainder = magnitude::shl_words_by(&remainder, self.shifts[level]);
+        remainder.resize(remainder.len().max(low.len()), 0);
+
+        for (word, low) in remainder.iter_mut().zip(low) {
+            *word |= low;
+        }
+
+        (quotient, remainder)
+    }
+
+    /// Multiply `words` by the power at `level`.
+    fn mul(&self, words: &[u64], level: usize) -> Vec<u64> {
+        magnitude::shl_words_by(
+            &magnitude::mul_words(words, &self.values[level]),
+            self.shifts[level],
+        )
+    }
+}
+
+/// Returns the digits of a magnitude, most significant first. Zero is a single `0` digit.
+pub(crate) fn to_digits(data: &[u8], radix: u8) -> Vec<u8> {
+    if radix.is_power_of_two() {
+        return to_digits_power_of_two(data, radix.trailing_zeros());
+    }
+
+    let words = magnitude::to_words(data);
+    let (chunk, _) = magnitude::largest_power(radix as u64);
+
+    // The number of chunks from the bit length, with one to spare for the rounding.
+    let bits = words.len() * 64 - words.last().map_or(0, |&top| top.leading_zeros() as usize);
+    let count = (bits as f64 / (chunk as f64).log2()).ceil() as usize + 1;
+
+    let powers = Powers::new(radix, count);
+
+    let mut chunks = vec![0u64; count];
+    to_chunks(words, 0, &powers, &mut chunks);
+
+    // The leading zero chunks are skipped, then the leading zeros of the first chunk.
+    let first = chunks
+        .iter()
+        .position(|&chunk| chunk != 0)
+        .unwrap_or(count - 1);
+    let mut digits = vec![0u8; (count - first) * powers.chunk_length];
+
+    for (chunk, chunk_digits) in chunks[first..]
+        .iter()
+        .zip(digits.chunks_mut(powers.chunk_length))
+    {
+        write_chunk(*chunk, radix, chunk_digits);
+    }
+
+    let leading_zeros = digits.iter().take_while(|&&digit| digit == 0).count();
+    digits.drain(..leading_zeros.min(digits.len() - 1));
+
+    digits
+}
+
+/// Write the digits of a chunk, with leading zeros, to `digits`.
+fn write_chunk(mut chunk: u64, radix: u8, digits: &mut [u8]) {
+    for digit in digits.iter_mut().rev() {
+        *digit = (chunk % radix as u64) as u8;
+        chunk /= radix as u64;
+    }
+}
+
+/// Write the word-sized digit chunks of `words` at the end of `chunks`, most significant first,
+/// splitting around the powers from `level` on. The chunks must be zeros and enough to hold
+/// the number.
+fn to_chunks(words: Vec<u64>, level: usize, powers: &Powers, chunks: &mut [u64]) {
+    if level == powers.values.len() || words.len() <= RECURSION_THRESHOLD {
+        let mut words = words;
+
+        for chunk in chunks.iter_mut().rev() {
+            if words.is_empty() {
+                break;
+            }
+
+            *chunk = magnitude::div_word_assign_words(&mut words, powers.chunk);
+        }
+
+        debug_assert!(words.is_empty(), "too few chunks for the number");
+
+        return;
+    }
+
+    let (quotient, remainder) = powers.div_rem(&words, level);
+    drop(words);
+
+    let (high, low) = chunks.split_at_mut(chunks.len() - powers.exponents[level]);
+
+    to_chunks(quotient, level + 1, powers, high);
+    to_chunks(remainder, level + 1, powers, low);
+}
+
+/// The digits of a magnitude, most significant first, written as they are consumed from
+/// either end.
+pub(crate) enum Digits {
+    /// A power-of-two radix, whose digits are read from the bits.
+    PowerOfTwo {
+        data: Vec<u8>,
+        bits: u32,
+        /// Positions of the digits not consumed yet, the least significant one at 0.
+        positions: Range<usize>,
+    },
+    /// Another radix, whose digit chunks are split out around the powers of the radix.
+    DivideAndConquer(ChunkedDigits),
+}
+
+/// The digits of a magnitude in a radix other than a power of two.
+pub(crate) struct ChunkedDigits {
+    radix: u8,
+    powers: Powers,
+    /// The parts not consumed yet, most significant first.
+    parts: VecDeque<Part>,
+    /// Leading zeros of the first chunk, still to skip.
+    leading_zeros: usize,
+    /// Number of digits not consumed yet.
+    remaining: usize,
+}
+
+/// A part of a number still to write as digits.
+enum Part {
+    /// A magnitude to write as `chunks` digit chunks, splitting around the powers from `level`
+    /// on.
+    Words {
+        words: Vec<u64>,
+        level: usize,
+        chunks: usize,
+    },
+    /// Digits already written and not consumed yet.
+    Digits(VecDeque<u8>),
+}
+
+/// Returns the `count` digits of a magnitude as an iterator.
+pub(crate) fn digits(data: &[u8], radix: u8, count: usize) -> Digits {
+    if radix.is_power_of_two() {
+        return Digits::PowerOfTwo {
+            data: magnitude::significant(data).to_vec(),
+            bits: radix.trailing_zeros(),
+            positions: 0..count,
+        };
+    }
+
+    let (_, chunk_length) = magnitude::largest_power(radix as u64);
+    let chunks = count.div_ceil(chunk_length);
+
+    Digits::DivideAndConquer(ChunkedDigits {
+        radix,
+        powers: Powers::new(radix, chunks),
+        parts: VecDeque::from([Part::Words {
+            words: magnitude::to_words(data),
+            level: 0,
+            chunks,
+        }]),
+        leading_zeros: chunks * chunk_length - count,
+        remaining: count,
+    })
+}
+
+impl ChunkedDigits {
+    /// Returns the next digit from the front, leading zeros included.
+    fn pop_front(&mut self) -> Option<u8> {
+        loop {
+            match self.parts.front_mut()? {
+                Part::Digits(digits) => match digits.pop_front() {
+                    Some(digit) => return Some(digit),
+                    None => drop(self.parts.pop_front()),
+                },
+                Part::Words { .. } => {
+                    let part = self.parts.pop_front()?;
+
+                    for part in self.split(part).into_iter().rev() {
+                        self.parts.push_front(part);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the next digit from the back.
+    fn pop_back(&mut self) -> Option<u8> {
+        loop {
+            match self.parts.back_mut()? {
+                Part::Digits(digits) => match digits.pop_back() {
+                    Some(digit) => return Some(digit),
+                    None => drop(self.parts.pop_back()),
+                },
+                Part::Words { .. } => {
+                    let part = self.parts.pop_back()?;
+
+                    for part in self.split(part) {
+                        self.parts.push_back(part);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Split a part around the power at its level, or write its digits after the last one.
+    fn split(&self, part: Part) -> Vec<Part> {
+        let Part::Words {
+            words,
+            level,
+            chunks,
+        } = part
+        else {
+            return vec![part];
+        };
+
+        if level < self.powers.values.len() {
+            let (quotient, remainder) = self.powers.div_rem(&words, level);
+            let exponent = self.powers.exponents[level];
+
+            return vec![
+                Part::Words {
+                    words: quotient,
+                    level: level + 1,
+                    chunks: chunks - exponent,
+                },
+                Part::Words {
+                    words: remainder,
+                    level: level + 1,
+                    chunks: exponent,
+                },
+            ];
+        }
+
+        let chunk_length = self.powers.chunk_length;
+        let mut words = words;
+        let mut digits = vec![0u8; chunks * chunk_length];
+
+        for chunk_digits in digits.rchunks_mut(chunk_length) {
+            if words.is_empty() {
+                break;
+            }
+
+            let chunk = magnitude::div_word_assign_words(&mut words, self.powers.chunk);
+            write_chunk(chunk, self.radix, chunk_digits);
+        }
+
+        vec![Part::Digits(VecDeque::from(digits))]
+    }
+}
+
+impl Iterator for Digits {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        match self {
+            Digits::PowerOfTwo {
+                data,
+                bits,
+                positions,
+            } => positions
+                .next_back()
+                .map(|position| power_of_two_digit(data, *bits, position)),
+            Digits::DivideAndConquer(digits) => {
+                if digits.remaining == 0 {
+                    return None;
+                }
+
+                while digits.leading_zeros > 0 {
+                    digits.leading_zeros -= 1;
+                    digits.pop_front();
+                }
+
+                digits.remaining -= 1;
+                digits.pop_front()
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self {
+            Digits::PowerOfTwo { positions, .. } => positions.len(),
+            Digits::DivideAndConquer(digits) => digits.remaining,
+        };
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for Digits {
+    fn next_back(&mut self) -> Option<u8> {
+        match self {
+            Digits::PowerOfTwo {
+                data,
+                bits,
+                positions,
+            } => positions
+                .next()
+                .map(|position| power_of_two_digit(data, *bits, position)),
+            Digits::DivideAndConquer(digits) => {
+                if digits.remaining == 0 {
+                    return None;
+                }
+
+                digits.remaining -= 1;
+                digits.pop_back()
+            }
+        }
+    }
+}
+
+impl ExactSizeIterator for Digits {}
+
+/// Returns the magnitude of digits given most significant first.
+pub(crate) fn from_digits(digits: &[u8], radix: u8) -> Vec<u8> {
+    if radix.is_power_of_two() {
+        return from_digits_power_of_two(digits, radix.trailing_zeros());
+    }
+
+    let (_, chunk_length) = magnitude::largest_power(radix as u64);
+
+    // Fold the digits into word-sized chunks; the first one takes the leftover digits.
+    let first_length = match digits.len() % chunk_length {
+        0 => chunk_length,
+        length => length,
+    };
+
+    let (first, rest) = digits.split_at(first_length.min(digits.len()));
+
+    let chunks: Vec<u64> = std::iter::once(first)
+        .chain(rest.chunks(chunk_length))
+        .map(|chunk| {
+            chunk
+                .iter()
+                .fold(0u64, |value, &digit| value * radix as u64 + digit as u64)
+        })
+        .collect();
+
+    let powers = Powers::new(radix, chunks.len());
+
+    magnitude::from_words(&from_chunks(&chunks, 0, &powers))
+}
+
+/// Digits given one at a time, most significant first, folded into word-sized chunks as they
+/// come so that only the chunks are held until the conversion.
+pub(crate) struct DigitFolder {
+    radix: u8,
+    chunk_length: usize,
+    /// The complete chunks, most significant first.
+    chunks: Vec<u64>,
+    /// The chunk being folded, of `partial_length` digits.
+    partial: u64,
+    partial_length: usize,
+}
+
+impl DigitFolder {
+    pub(crate) fn new(radix: u8) -> Self {
+        let (_, chunk_length) = magnitude::largest_power(radix as u64);
+
+        DigitFolder {
+            radix,
+            chunk_length,
+            chunks: Vec::new(),
+            partial: 0,
+            partial_length: 0,
+        }
+    }
+
+    /// Returns whether no digit was pushed yet.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.chunks.is_empty() && self.partial_length == 0
+    }
+
+    pub(crate) fn push(&mut self, digit: u8) {
+        self.partial = self.partial * self.radix as u64 + digit as u64;
+        self.partial_length += 1;
+
+        if self.partial_length == self.chunk_length {
+            self.chunks.push(self.partial);
+            self.partial = 0;
+            self.partial_length = 0;
+        }
+    }
+
+    /// Returns the magnitude of the digits pushed.
+    pub(crate) fn finish(self) -> Vec<u8> {
+        let powers = Powers::new(self.radix, self.chunks.len());
+        let mut words = from_chunks(&self.chunks, 0, &powers);
+
+        // The digits of the last chunk, which is not complete, go under the others.
+        if self.partial_length > 0 {
+            let shift = (self.radix as u64).pow(self.partial_length as u32);
+
+            words = magnitude::mul_words(&words, &[shift]);
+            words.resize(words.len() + 1, 0);
+            magnitude::add_words_at(&mut words, &[self.partial], 0);
+        }
+
+        magnitude::from_words(&words)
+    }
+}
+
+/// Returns the words of word-sized digit chunks given most significant first, splitting around
+/// the powers from `level` on.
+fn from_chunks(chunks: &[u64], level: usize, powers: &Powers) -> Vec<u64> {
+    if level == powers.values.len() || chunks.len() <= RECURSION_THRESHOLD {
+        let mut words: Vec<u64> = Vec::with_capacity(chunks.len());
+
+        for &chunk in chunks {
+            let mut carry = chunk as u128;
+
+            for word in words.iter_mut() {
+                let value = *word as u128 * powers.chunk as u128 + carry;
+
+                *word = value as u64;
+                carry = value >> 64;
+            }
+
+            if carry > 0 {
+                words.push(carry as u64);
+            }
+        }
+
+        return words;
+    }
+
+    let (high, low) = chunks.split_at(chunks.len().saturating_sub(powers.exponents[level]));
+
+    let high = from_chunks(high, level + 1, powers);
+    let low = from_chunks(low, level + 1, powers);
+
+    let mut result = powers.mul(&high, level);
+    result.resize(result.len().max(low.len()) + 1, 0);
+    magnitude::add_words_at(&mut result, &low, 0);
+
+    result
+}
+
+/// Returns the digits of a magnitude in radix `2^bits`, most significant first.
+fn to_digits_power_of_two(data: &[u8], bits: u32) -> Vec<u8> {
+    let data = magnitude::significant(data);
+    let length = data.len() * 8 - data.last().map_or(0, |&top| top.leading_zeros() as usize);
+    let count = length.div_ceil(bits as usize).max(1);
+
+    (0..count)
+        .rev()
+        .map(|position| power_of_two_digit(data, bits, position))
+        .collect()
+}
+
+/// Returns the digit at `position` of a magnitude in radix `2^bits`, the least significant one
+/// at 0.
+fn power_of_two_digit(data: &[u8], bits: u32, position: usize) -> u8 {
+    let bit = position * bits as usize;
+    let byte = bit / 8;
+
+    let low = *data.get(byte).unwrap_or(&0) as u16;
+    let high = *data.get(byte + 1).unwrap_or(&0) as u16;
+
+    ((low | high << 8) >> (bit % 8) & ((1 << bits) - 1)) as u8
+}
+
+/// Returns the magnitude of digits in radix `2^bits`, given most significant first.
+fn from_digits_power_of_two(digits: &[u8], bits: u32) -> Vec<u8> {
+    let mut data = vec![0u8; (digits.len() * bits as usize).div_ceil(8) + 1];
+
+    for (index, &digit) in digits.iter().rev().enumerate() {
+        let position = index * bits as usize;
+        let value = (digit as u16) << (position % 8);
+
+        data[position / 8] |= value as u8;
+        data[position / 8 + 1] |= (value >> 8) as u8;
+    }
+
+    magnitude::trim(&mut data);
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A pseudo-random magnitude of the given length.
+    fn magnitude_of_length(length: usize) -> Vec<u8> {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+
+        (0..length)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+
+                state as u8
+            })
+            .collect()
+    }
+
+    /// Digit-by-digit conversion, as a reference.
+    fn to_digits_slowly(data: &[u8], radix: u8) -> Vec<u8> {
+        let mut data = magnitude::significant(data).to_vec();
+        let mut digits = Vec::new();
+
+        while !data.is_empty() {
+            digits.push(magnitude::div_word_assign(&mut data, radix as u64) as u8);
+        }
+
+        digits.reverse();
+
+        digits
+    }
+
+    mod divide_and_conquer {
+        use super::*;
+
+        #[test]
+        fn should_match_the_digit_by_digit_conversion() {
+            // Given
+            let data = magnitude_of_length(3000);
+
+            for radix in [3, 10, 36] {
+                // When
+                let digits = to_digits(&data, radix);
+
+                // Then
+                assert_eq!(digits, to_digits_slowly(&data, radix));
+                assert_eq!(from_digits(&digits, radix), data);
+            }
+        }
+
+        #[test]
+        fn should_keep_the_zeros_inside_the_number() {
+            // Given: 10^5000, whose chunks are all zeros but the first one.
+            let mut digits = vec![0u8; 5001];
+            digits[0] = 1;
+
+            // When
+            let data = from_digits(&digits, 10);
+
+            // Then
+            assert_eq!(to_digits(&data, 10), digits);
+        }
+
+        #[test]
+        fn should_ignore_leading_zero_digits() {
+            // Given
+            let mut digits = vec![0u8; 2000];
+            digits.extend_from_slice(&[4, 2]);
+
+            // When
+            let data = from_digits(&digits, 10);
+
+            // Then
+            assert_eq!(data, vec![42]);
+        }
+
+        #[test]
+        #[ignore = "timing, run with `cargo test --release -- --ignored`"]
+        fn should_convert_a_million_digits_in_half_a_second() {
+            // Given
+            let digits: Vec<u8> = magnitude_of_length(1_000_000)
+                .iter()
+                .map(|&byte| byte % 10)
+                .collect();
+
+            // When
+            let start = std::time::Instant::now();
+            let data = from_digits(&digits, 10);
+            let parse_time = start.elapsed();
+
+            let start = std::time::Instant::now();
+            let printed = to_digits(&data, 10);
+            let print_time = start.elapsed();
+
+            // Then
+            let expected = &digits[digits.iter().position(|&digit| digit != 0).unwrap()..];
+
+            assert_eq!(printed, expected);
+            assert!(parse_time.as_millis() < 500, "parsed in {parse_time:?}");
+            assert!(print_time.as_millis() < 500, "printed in {print_time:?}");
+        }
+    }
+
+    mod digit_folder {
+        use super::*;
+
+        #[test]
+        fn should_match_the_conversion_of_the_whole_digits() {
+            // Given
+            let data = magnitude_of_length(1000);
+
+            for radix in [2, 3, 10, 16, 36] {
+                let digits = to_digits(&data, radix);
+
+                // Lengths on and off the chunk boundaries.
+                for length in [0, 1, 19, 20, 63, 64, 500, digits.len()] {
+                    let digits = &digits[..length];
+                    let mut folder = DigitFolder::new(radix);
+
+                    // When
+                    for &digit in digits {
+                        folder.push(digit);
+                    }
+
+                    // Then
+                    assert_eq!(folder.finish(), from_digits(digits, radix));
+                }
+            }
+        }
+    }
+
+    mod power_of_two {
+        use super::*;
+
+        #[test]
+        fn should_slice_bits_across_bytes() {
+            // Given
+            let data = vec![0x77, 0x39, 0x05]; // 0o1234567
+
+            // When
+            let digits = to_digits(&data, 8);
+
+            // Then
+            assert_eq!(digits, vec![1, 2, 3, 4, 5, 6, 7]);
+            assert_eq!(from_digits(&digits, 8), data);
+        }
+
+        #[test]
+        fn should_match_the_digit_by_digit_conversion() {
+            // Given
+            let data = magnitude_of_length(1000);
+
+            for radix in [2, 4, 8, 16, 32] {
+                // When
+                let digits = to_digits(&data, radix);
+
+                // Then
+                assert_eq!(digits, to_digits_slowly(&data, radix));
+                assert_eq!(from_digits(&digits, radix), data);
+            }
+        }
+
+        #[test]
+        fn should_convert_zero() {
+            // When / Then
+            assert_eq!(to_digits(&[], 16), vec![0]);
+            assert_eq!(from_digits(&[0, 0], 16), Vec::<u8>::new());
+        }
+    }
+}
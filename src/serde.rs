@@ -0,0 +1,392 @@
+//! `Serialize` and `Deserialize` for `BigInt`, and modules for `#[serde(with = ...)]` that pick
+//! another representation.
+//!
+//! Human-readable formats get a decimal string. Binary formats get a byte string: a sign byte
+//! (`0` for non-negative, `1` for negative) followed by the big-endian magnitude.
+
+use std::fmt;
+
+use ::serde::de::{self, Deserializer, SeqAccess, Visitor};
+use ::serde::ser::Serializer;
+use ::serde::{Deserialize, Serialize};
+
+use crate::{Base, BigInt, Sign};
+
+impl Serialize for BigInt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(&self.to_string(Base::Decimal));
+        }
+
+        let mut bytes = vec![self.is_negative() as u8];
+        bytes.extend(self.magnitude().iter().rev().skip_while(|&&byte| byte == 0));
+
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigInt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BigIntVisitor {
+                base: Base::Decimal,
+            })
+        } else {
+            deserializer.deserialize_bytes(BigIntVisitor {
+                base: Base::Decimal,
+            })
+        }
+    }
+}
+
+/// Accepts a `BigInt` as a string of digits in `base`, a sign-and-magnitude byte string or a
+/// primitive integer.
+struct BigIntVisitor {
+    /// Base of the digits of a string.
+    base: Base,
+}
+
+impl<'de> Visitor<'de> for BigIntVisitor {
+    type Value = BigInt;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "an integer, or a string of digits in radix {}",
+            self.base.to_radix()
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<BigInt, E> {
+        let (sign, digits) = match value.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", value.strip_prefix('+').unwrap_or(value)),
+        };
+
+        // Hexadecimal strings may carry a `0x` prefix.
+        let digits = match self.base {
+            Base::Hexadecimal => digits
+                .strip_prefix("0x")
+                .or_else(|| digits.strip_prefix("0X"))
+                .unwrap_or(digits),
+            _ => digits,
+        };
+
+        BigInt::from_str_radix(&format!("{}{}", sign, digits), self.base).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<BigInt, E> {
+        match value {
+            [0, magnitude @ ..] => Ok(BigInt::from_bytes_be(Sign::Plus, magnitude)),
+            [1, magnitude @ ..] => Ok(BigInt::from_bytes_be(Sign::Minus, magnitude)),
+            _ => Err(E::invalid_value(de::Unexpected::Bytes(value), &self)),
+        }
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut sequence: A) -> Result<BigInt, A::Error> {
+        let mut bytes = Vec::with_capacity(sequence.size_hint().unwrap_or(0));
+
+        while let Some(byte) = sequence.next_element()? {
+            bytes.push(byte);
+        }
+
+        self.visit_bytes(&bytes)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<BigInt, E> {
+        Ok(BigInt::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<BigInt, E> {
+        Ok(BigInt::from(value))
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<BigInt, E> {
+        Ok(BigInt::from(value))
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<BigInt, E> {
+        Ok(BigInt::from(value))
+    }
+}
+
+/// A `BigInt` as a hexadecimal string such as `"-0xff"`, in every format. Deserializing also
+/// accepts uppercase digits, a `+` sign and no `0x` prefix.
+pub mod hex {
+    use super::*;
+
+    /// Serialize a `BigInt` as a hexadecimal string.
+    pub fn serialize<S: Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:#x}", value))
+    }
+
+    /// Deserialize a `BigInt` from a hexadecimal string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+        deserializer.deserialize_str(BigIntVisitor {
+            base: Base::Hexadecimal,
+        })
+    }
+}
+
+/// A `BigInt` as a native number of the format. Human-readable formats get the smallest
+/// primitive integer the value fits in, and deserializing also accepts a decimal string, for the
+/// formats that write large numbers as strings. Binary formats, which do not record the type of
+/// a value, always get an `i128`.
+///
+/// Values beyond 128 bits, or beyond the range of `i128` in binary formats, cannot be
+/// serialized: use the default representation or `hex` for those.
+pub mod number {
+    use super::*;
+
+    /// Serialize a `BigInt` as the smallest primitive integer it fits in, or as an `i128` in
+    /// binary formats.
+    pub fn serialize<S: Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return match i128::try_from(value) {
+                Ok(value) => serializer.serialize_i128(value),
+                Err(_) => Err(::serde::ser::Error::custom(
+                    "BigInt out of range for a number in a binary format, beyond i128",
+                )),
+            };
+        }
+
+        if let Ok(value) = i64::try_from(value) {
+            serializer.serialize_i64(value)
+        } else if let Ok(value) = u64::try_from(value) {
+            serializer.serialize_u64(value)
+        } else if let Ok(value) = i128::try_from(value) {
+            serializer.serialize_i128(value)
+        } else if let Ok(value) = u128::try_from(value) {
+            serializer.serialize_u128(value)
+        } else {
+            Err(::serde::ser::Error::custom(
+                "BigInt out of range for a number, beyond 128 bits",
+            ))
+        }
+    }
+
+    /// Deserialize a `BigInt` from an integer or a decimal string, or from an `i128` in binary
+    /// formats.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+        let visitor = BigIntVisitor {
+            base: Base::Decimal,
+        };
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(visitor)
+        } else {
+            deserializer.deserialize_i128(visitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::serde::{Deserialize, Serialize};
+    use serde_test::{
+        assert_de_tokens, assert_de_tokens_error, assert_ser_tokens_error, assert_tokens,
+        Configure, Token,
+    };
+
+    use crate::BigInt;
+
+    mod default {
+        use super::*;
+
+        #[test]
+        fn should_use_a_decimal_string_in_human_readable_formats() {
+            // Given
+            let value: BigInt = "-123456789012345678901234567890".parse().unwrap();
+
+            // When / Then
+            assert_tokens(
+                &value.readable(),
+                &[Token::Str("-123456789012345678901234567890")],
+            );
+        }
+
+        #[test]
+        fn should_use_sign_and_magnitude_bytes_in_binary_formats() {
+            // When / Then
+            assert_tokens(
+                &BigInt::from(-0x0102i16).compact(),
+                &[Token::Bytes(&[1, 0x01, 0x02])],
+            );
+            assert_tokens(&BigInt::from(0u8).compact(), &[Token::Bytes(&[0])]);
+        }
+
+        #[test]
+        fn should_reject_malformed_input() {
+            // When / Then
+            assert_de_tokens_error::<serde_test::Readable<BigInt>>(
+                &[Token::Str("12?")],
+                "invalid digit at byte 2",
+            );
+            assert_de_tokens_error::<serde_test::Compact<BigInt>>(
+                &[Token::Bytes(&[2, 1])],
+                "invalid value: byte array, expected an integer, or a string of digits in radix 10",
+            );
+        }
+    }
+
+    mod number {
+        use ::serde::de::{self, Deserializer, Visitor};
+
+        use super::*;
+
+        /// A binary format holding a single `i128` that, like bincode, cannot tell the type of
+        /// what it holds.
+        struct BinaryI128(i128);
+
+        impl<'de> Deserializer<'de> for BinaryI128 {
+            type Error = de::value::Error;
+
+            fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+                Err(de::Error::custom("the format is not self-describing"))
+            }
+
+            fn deserialize_i128<V: Visitor<'de>>(
+                self,
+                visitor: V,
+            ) -> Result<V::Value, Self::Error> {
+                visitor.visit_i128(self.0)
+            }
+
+            fn is_human_readable(&self) -> bool {
+                false
+            }
+
+            ::serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+                option unit unit_struct newtype_struct seq tuple tuple_struct map struct enum
+                identifier ignored_any
+            }
+        }
+
+        #[test]
+        fn should_read_an_i128_from_binary_formats() {
+            for value in [0, -5, i128::MIN, i128::MAX] {
+                // When
+                let result = crate::serde::number::deserialize(BinaryI128(value));
+
+                // Then
+                assert_eq!(result, Ok(BigInt::from(value)));
+            }
+        }
+    }
+
+    mod with {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Record {
+            #[serde(with = "crate::serde::hex")]
+            key: BigInt,
+
+            #[serde(with = "crate::serde::number")]
+            id: BigInt,
+        }
+
+        #[test]
+        fn should_use_the_requested_representations() {
+            // Given
+            let record = Record {
+                key: BigInt::from(-255i16),
+                id: BigInt::from(u64::MAX),
+            };
+
+            // When / Then
+            assert_tokens(
+                &record.readable(),
+                &[
+                    Token::Struct {
+                        name: "Record",
+                        len: 2,
+                    },
+                    Token::Str("key"),
+                    Token::Str("-0xff"),
+                    Token::Str("id"),
+                    Token::U64(u64::MAX),
+                    Token::StructEnd,
+                ],
+            );
+        }
+
+        #[test]
+        fn should_read_other_spellings() {
+            for key in ["ABCD", "+0xabcd", "+0XABCD"] {
+                // Given
+                let record = Record {
+                    key: BigInt::from(0xABCDu16),
+                    id: "123456789012345678901234567890123456789012"
+                        .parse()
+                        .unwrap(),
+                };
+
+                // When / Then
+                assert_de_tokens(
+                    &record.readable(),
+                    &[
+                        Token::Struct {
+                            name: "Record",
+                            len: 2,
+                        },
+                        Token::Str("key"),
+                        Token::Str(key),
+                        Token::Str("id"),
+                        Token::Str("123456789012345678901234567890123456789012"),
+                        Token::StructEnd,
+                    ],
+                );
+            }
+        }
+
+        #[test]
+        fn should_not_write_numbers_beyond_128_bits() {
+            // Given
+            let record = Record {
+                key: BigInt::from(0u8),
+                id: BigInt::from(1u8) << 128,
+            };
+
+            // When / Then
+            assert_ser_tokens_error(
+                &record.readable(),
+                &[
+                    Token::Struct {
+                        name: "Record",
+                        len: 2,
+                    },
+                    Token::Str("key"),
+                    Token::Str("0x0"),
+                    Token::Str("id"),
+                ],
+                "BigInt out of range for a number, beyond 128 bits",
+            );
+        }
+
+        #[test]
+        fn should_not_write_numbers_beyond_i128_in_binary_formats() {
+            // Given
+            let record = Record {
+                key: BigInt::from(0u8),
+                id: BigInt::from(u128::MAX),
+            };
+
+            // When / Then
+            assert_ser_tokens_error(
+                &record.compact(),
+                &[
+                    Token::Struct {
+                        name: "Record",
+                        len: 2,
+                    },
+                    Token::Str("key"),
+                    Token::Str("0x0"),
+                    Token::Str("id"),
+                ],
+                "BigInt out of range for a number in a binary format, beyond i128",
+            );
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! A versioned binary container for `BigInt` values, with a checksum on every value so that
+//! truncated or corrupted snapshots are detected when they are read back.
+//!
+//! All the fields are little-endian:
+//!
+//! ```text
+//! header:  magic "BIGN" | version: u16 | count: u32
+//! value:   flags: u8 | length: u64 | limbs | crc32: u32
+//! ```
+//!
+//! Bit 0 of the flags is the sign and bits 1 and 2 give the limb size, `1 << n` bytes. The
+//! length counts limbs, and the CRC-32 covers the flags, the length and the limbs.
+
+use crate::decode;
+use crate::BigInt;
+
+/// Identifies a snapshot.
+const MAGIC: [u8; 4] = *b"BIGN";
+
+/// The version written, and the only one read.
+const VERSION: u16 = 1;
+
+/// Flag of a negative value.
+const NEGATIVE: u8 = 0x01;
+
+/// Flag bits holding the limb size.
+const LIMB_SIZE_MASK: u8 = 0x06;
+
+/// The limb size written, 8 bytes.
+const LIMB_SIZE_CODE: u8 = 3;
+
+/// The table of the CRC-32 of IEEE 802.3, one entry per byte value.
+const CRC_TABLE: [u32; 256] = crc_table();
+
+/// The error returned when a snapshot cannot be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The data does not start with the magic number of a snapshot.
+    BadMagic,
+    /// The snapshot was written in a version this code cannot read.
+    UnsupportedVersion(u16),
+    /// The data ends before the last value does.
+    Truncated,
+    /// The flags of the value at this index have unknown bits set.
+    InvalidFlags(usize),
+    /// The checksum of the value at this index does not match its contents.
+    ChecksumMismatch(usize),
+    /// Bytes follow the last value.
+    TrailingData,
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            SnapshotError::BadMagic => write!(formatter, "not a BigInt snapshot"),
+            SnapshotError::UnsupportedVersion(version) => {
+                write!(formatter, "unsupported snapshot version {}", version)
+            }
+            SnapshotError::Truncated => write!(formatter, "snapshot truncated"),
+            SnapshotError::InvalidFlags(index) => {
+                write!(
+                    formatter,
+                    "invalid flags for value {} of the snapshot",
+                    index
+                )
+            }
+            SnapshotError::ChecksumMismatch(index) => {
+                write!(
+                    formatter,
+                    "checksum mismatch for value {} of the snapshot",
+                    index
+                )
+            }
+            SnapshotError::TrailingData => write!(formatter, "data after the end of the snapshot"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// Compute the CRC-32 table, for the reflected polynomial `0xEDB88320`.
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            value = if value & 1 != 0 {
+                (value >> 1) ^ 0xEDB8_8320
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+
+        table[index] = value;
+        index += 1;
+    }
+
+    table
+}
+
+/// Returns the CRC-32 of IEEE 802.3 (as in zlib and PNG) of `data`.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Returns `length` bytes of `data` from `position`, or a truncation error.
+fn take(data: &[u8], position: usize, length: usize) -> Result<&[u8], SnapshotError> {
+    decode::take(data, position, length).map_err(|_| SnapshotError::Truncated)
+}
+
+impl BigInt {
+    /// Returns a snapshot of `values`, to be read back with [`BigInt::from_snapshot`].
+    ///
+    /// Panics if there are more than `u32::MAX` values, the most the header can count.
+    pub fn to_snapshot(values: &[BigInt]) -> Vec<u8> {
+        let count = u32::try_from(values.len()).expect("more than u32::MAX values to snapshot");
+
+        let mut result = MAGIC.to_vec();
+        result.extend_from_slice(&VERSION.to_le_bytes());
+        result.extend_from_slice(&count.to_le_bytes());
+
+        let limb_size = 1 << LIMB_SIZE_CODE;
+
+        for value in values {
+            let mut limbs = value.magnitude().to_vec();
+            limbs.resize(limbs.len().div_ceil(limb_size) * limb_size, 0);
+
+            let start = result.len();
+            let flags = (LIMB_SIZE_CODE << 1) | if value.is_negative() { NEGATIVE } else { 0 };
+
+            result.push(flags);
+            result.extend_from_slice(&((limbs.len() / limb_size) as u64).to_le_bytes());
+            result.extend_from_slice(&limbs);
+
+            let checksum = crc32(&result[start..]);
+            result.extend_from_slice(&checksum.to_le_bytes());
+        }
+
+        result
+    }
+
+    /// Read back the values of a snapshot, checking that it is complete and intact.
+    pub fn from_snapshot(data: &[u8]) -> Result<Vec<BigInt>, SnapshotError> {
+        if take(data, 0, 4)? != MAGIC {
+            return Err(SnapshotError::BadMagic);
+        }
+
+        let version = u16::from_le_bytes(take(data, 4, 2)?.try_into().unwrap());
+
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let count = u32::from_le_bytes(take(data, 6, 4)?.try_into().unwrap());
+
+        let mut values = Vec::new();
+        let mut position = 10;
+
+        for index in 0..count as usize {
+            let flags = take(data, position, 1)?[0];
+
+            if flags & !(NEGATIVE | LIMB_SIZE_MASK) != 0 {
+                return Err(SnapshotError::InvalidFlags(index));
+            }
+
+            let limb_size = 1usize << ((flags & LIMB_SIZE_MASK) >> 1);
+            let length = u64::from_le_bytes(take(data, position + 1, 8)?.try_into().unwrap());
+
+            // The flags and length, then the limbs.
+            let entry_length = usize::try_from(length)
+                .ok()
+                .and_then(|length| length.checked_mul(limb_size))
+                .and_then(|length| length.checked_add(9))
+                .ok_or(SnapshotError::Truncated)?;
+
+            let entry = take(data, position, entry_length)?;
+            let checksum =
+                u32::from_le_bytes(take(data, position + entry.len(), 4)?.try_into().unwrap());
+
+            if crc32(entry) != checksum {
+                return Err(SnapshotError::ChecksumMismatch(index));
+            }
+
+            values.push(BigInt::from_magnitude(
+                entry[9..].to_vec(),
+                flags & NEGATIVE != 0,
+            ));
+            position += entry.len() + 4;
+        }
+
+        if position != data.len() {
+            return Err(SnapshotError::TrailingData);
+        }
+
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Some values of every sign and size.
+    fn values() -> Vec<BigInt> {
+        vec![
+            BigInt::from(0u8),
+            BigInt::from(-1i8),
+            BigInt::from(u64::MAX),
+            -(BigInt::from(3u8) << 10_000) + 7u8,
+        ]
+    }
+
+    mod checksum {
+        use super::*;
+
+        #[test]
+        fn should_compute_the_standard_crc_32() {
+            // When / Then
+            assert_eq!(crc32(b""), 0);
+            assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        }
+    }
+
+    mod round_trip {
+        use super::*;
+
+        #[test]
+        fn should_read_back_every_value() {
+            // Given
+            let values = values();
+
+            // When
+            let snapshot = BigInt::to_snapshot(&values);
+
+            // Then
+            assert_eq!(snapshot[..6], [b'B', b'I', b'G', b'N', 1, 0]);
+            assert_eq!(BigInt::from_snapshot(&snapshot), Ok(values));
+            assert_eq!(BigInt::from_snapshot(&BigInt::to_snapshot(&[])), Ok(vec![]));
+        }
+
+        #[test]
+        fn should_read_any_limb_size() {
+            // Given: -0x0102 in two 1-byte limbs.
+            let mut snapshot = b"BIGN\x01\x00\x01\x00\x00\x00".to_vec();
+            let entry = [0x01, 2, 0, 0, 0, 0, 0, 0, 0, 0x02, 0x01];
+
+            snapshot.extend_from_slice(&entry);
+            snapshot.extend_from_slice(&crc32(&entry).to_le_bytes());
+
+            // When
+            let values = BigInt::from_snapshot(&snapshot);
+
+            // Then
+            assert_eq!(values, Ok(vec![BigInt::from(-0x0102i16)]));
+        }
+    }
+
+    mod damage {
+        use super::*;
+
+        #[test]
+        fn should_detect_truncation() {
+            // Given
+            let snapshot = BigInt::to_snapshot(&values());
+
+            for length in 0..snapshot.len() {
+                // When
+                let result = BigInt::from_snapshot(&snapshot[..length]);
+
+                // Then
+                assert_eq!(result, Err(SnapshotError::Truncated), "length {}", length);
+            }
+        }
+
+        #[test]
+        fn should_detect_flipped_bits() {
+            // Given
+            let snapshot = BigInt::to_snapshot(&values());
+
+            for (position, index) in [(20, 0), (35, 1), (55, 2), (100, 3), (snapshot.len() - 1, 3)]
+            {
+                let mut damaged = snapshot.clone();
+                damaged[position] ^= 0x10;
+
+                // When
+                let result = BigInt::from_snapshot(&damaged);
+
+                // Then
+                assert_eq!(result, Err(SnapshotError::ChecksumMismatch(index)));
+            }
+        }
+
+        #[test]
+        fn should_reject_foreign_data() {
+            // Given
+            let snapshot = BigInt::to_snapshot(&values());
+
+            let mut magic = snapshot.clone();
+            magic[0] = b'X';
+
+            let mut version = snapshot.clone();
+            version[4] = 2;
+
+            let mut flags = snapshot.clone();
+            flags[10] |= 0x80;
+
+            let mut trailing = snapshot.clone();
+            trailing.push(0);
+
+            // When / Then
+            assert_eq!(BigInt::from_snapshot(&magic), Err(SnapshotError::BadMagic));
+            assert_eq!(
+                BigInt::from_snapshot(&version),
+                Err(SnapshotError::UnsupportedVersion(2))
+            );
+            assert_eq!(
+                BigInt::from_snapshot(&flags),
+                Err(SnapshotError::InvalidFlags(0))
+            );
+            assert_eq!(
+                BigInt::from_snapshot(&trailing),
+                Err(SnapshotError::TrailingData)
+            );
+        }
+    }
+}
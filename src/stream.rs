@@ -0,0 +1,234 @@
+//! Reading and writing `BigInt` values as text through `std::io`, for numbers too large to
+//! comfortably hold as a `String` next to their digits.
+
+use std::io::{self, Read, Write};
+
+use crate::parse::{digit_value, ParseBigIntError, ParseErrorKind};
+use crate::radix::DigitFolder;
+use crate::{Base, BigInt};
+
+/// Size of the chunks read or written at once.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Returns a parse error as an I/O error of kind `InvalidData`.
+fn invalid_data(kind: ParseErrorKind, position: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        ParseBigIntError::new(kind, position),
+    )
+}
+
+impl BigInt {
+    /// Read a number in the provided base until the end of `reader`. The digits may be preceded
+    /// by a single `+` or `-` and surrounded by ASCII whitespace, such as a final newline.
+    ///
+    /// The digits are folded into word-sized chunks as they are read, so the memory held
+    /// before the conversion is about the size of the number, not of its text. Malformed input
+    /// is an error of kind `InvalidData` wrapping a [`ParseBigIntError`].
+    pub fn read_from<R: Read>(mut reader: R, base: Base) -> io::Result<Self> {
+        let radix = base.to_radix();
+
+        let mut buffer = vec![0u8; BUFFER_SIZE];
+        let mut digits = DigitFolder::new(radix);
+        let mut position = 0;
+
+        let mut negative = false;
+        let mut started = false;
+        let mut seen_digit = false;
+        let mut finished = false;
+
+        loop {
+            let length = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(length) => length,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+
+            for &byte in &buffer[..length] {
+                if byte.is_ascii_whitespace() {
+                    // Whitespace ends the number, once it has started.
+                    finished |= started;
+                } else if finished {
+                    return Err(invalid_data(ParseErrorKind::InvalidDigit, position));
+                } else if byte == b'-' || byte == b'+' {
+                    if started {
+                        return Err(invalid_data(ParseErrorKind::MisplacedSign, position));
+                    }
+
+                    negative = byte == b'-';
+                    started = true;
+                } else {
+                    match digit_value(byte as char) {
+                        Some(value) if value < radix => {
+                            // Leading zeros are dropped.
+                            if value != 0 || !digits.is_empty() {
+                                digits.push(value);
+                            }
+
+                            started = true;
+                            seen_digit = true;
+                        }
+                        Some(_) => {
+                            return Err(invalid_data(ParseErrorKind::DigitOutOfRange, position));
+                        }
+                        _ => return Err(invalid_data(ParseErrorKind::InvalidDigit, position)),
+                    }
+                }
+
+                position += 1;
+            }
+        }
+
+        if !seen_digit {
+            return Err(invalid_data(ParseErrorKind::Empty, position));
+        }
+
+        Ok(BigInt::from_magnitude(digits.finish(), negative))
+    }
+
+    /// Write the number in the provided base to `writer`, like [`BigInt::to_string`] but
+    /// without building a `String`.
+    ///
+    /// The digits are written chunk by chunk as they are produced, through a fixed-size buffer.
+    pub fn write_to<W: Write>(&self, mut writer: W, base: Base) -> io::Result<()> {
+        if self.is_negative() {
+            writer.write_all(b"-")?;
+        }
+
+        let mut buffer = Vec::with_capacity(BUFFER_SIZE);
+
+        for digit in self.digits(base) {
+            buffer.push(if digit < 10 {
+                b'0' + digit
+            } else {
+                b'A' + (digit - 10)
+            });
+
+            if buffer.len() == BUFFER_SIZE {
+                writer.write_all(&buffer)?;
+                buffer.clear();
+            }
+        }
+
+        writer.write_all(&buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::{Base, BigInt, ParseBigIntError, ParseErrorKind};
+
+    /// Returns the kind and position of the parse error wrapped in an I/O error.
+    fn parse_error(error: io::Error) -> (ParseErrorKind, usize) {
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let error = error
+            .into_inner()
+            .unwrap()
+            .downcast::<ParseBigIntError>()
+            .unwrap();
+
+        (error.kind(), error.position())
+    }
+
+    mod read {
+        use super::*;
+
+        #[test]
+        fn should_read_across_buffer_boundaries() {
+            // Given: more digits than fit in one buffer, then a newline.
+            let mut text = "-1".to_string();
+            text.push_str(&"0".repeat(200_000));
+            text.push('\n');
+
+            // When
+            let value = BigInt::read_from(text.as_bytes(), Base::Decimal).unwrap();
+
+            // Then
+            assert_eq!(value.decimal_digit_count(), 200_001);
+            assert!(value.is_negative());
+            assert_eq!(value.leading_digits(3, Base::Decimal), "100");
+        }
+
+        #[test]
+        fn should_read_any_base() {
+            // When / Then
+            assert_eq!(
+                BigInt::read_from(&b"  ff\r\n"[..], Base::Hexadecimal).unwrap(),
+                BigInt::from(255u8)
+            );
+            assert_eq!(
+                BigInt::read_from(&b"+0000"[..], Base::Octal).unwrap(),
+                BigInt::from(0u8)
+            );
+        }
+
+        #[test]
+        fn should_report_malformed_input() {
+            // Given
+            let read = |data: &str, base| {
+                parse_error(BigInt::read_from(data.as_bytes(), base).unwrap_err())
+            };
+
+            // When / Then
+            assert_eq!(read("", Base::Decimal), (ParseErrorKind::Empty, 0));
+            assert_eq!(read(" - \n", Base::Decimal), (ParseErrorKind::Empty, 4));
+            assert_eq!(
+                read("12 3", Base::Decimal),
+                (ParseErrorKind::InvalidDigit, 3)
+            );
+            assert_eq!(
+                read("1-2", Base::Decimal),
+                (ParseErrorKind::MisplacedSign, 1)
+            );
+            assert_eq!(
+                read("19", Base::Octal),
+                (ParseErrorKind::DigitOutOfRange, 1)
+            );
+            assert_eq!(read("1é", Base::Decimal), (ParseErrorKind::InvalidDigit, 1));
+        }
+    }
+
+    mod write {
+        use super::*;
+
+        #[test]
+        fn should_match_to_string() {
+            for base in [
+                Base::Binary,
+                Base::Decimal,
+                Base::Hexadecimal,
+                Base::from_radix(36).unwrap(),
+            ] {
+                // Given
+                let value = -(BigInt::from(7u8) << 5000) + 12345u16;
+                let mut output = Vec::new();
+
+                // When
+                value.write_to(&mut output, base).unwrap();
+
+                // Then
+                assert_eq!(String::from_utf8(output).unwrap(), value.to_string(base));
+            }
+        }
+
+        #[test]
+        fn should_round_trip() {
+            // Given: more digits than fit in one buffer.
+            let value = BigInt::from(3u8) << 300_000;
+            let mut output = Vec::new();
+
+            // When
+            value.write_to(&mut output, Base::Decimal).unwrap();
+
+            // Then
+            assert_eq!(
+                BigInt::read_from(output.as_slice(), Base::Decimal).unwrap(),
+                value
+            );
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! Helpers shared by the unit tests.
+
+use crate::{Base, BigInt};
+
+/// Returns the `BigInt` written in decimal as `value`.
+pub(crate) fn number(value: &str) -> BigInt {
+    BigInt::from_string(value.to_string(), Base::Decimal)
+}
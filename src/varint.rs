@@ -0,0 +1,283 @@
+//! Variable-length encodings of `BigInt` values: unsigned LEB128, signed LEB128 and the zigzag
+//! varints of Protocol Buffers.
+//!
+//! Each byte carries 7 bits of the value, least significant first, and has its high bit set
+//! when more bytes follow.
+
+use crate::radix;
+use crate::BigInt;
+
+/// The error returned when a varint ends before its last byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruncatedVarintError {
+    /// Number of bytes that were available.
+    length: usize,
+}
+
+impl TruncatedVarintError {
+    /// Returns the number of bytes that were available.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+}
+
+impl std::fmt::Display for TruncatedVarintError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            formatter,
+            "varint truncated, no last byte in {} bytes",
+            self.length
+        )
+    }
+}
+
+impl std::error::Error for TruncatedVarintError {}
+
+impl BigInt {
+    /// Returns the magnitude in unsigned LEB128. The sign is dropped.
+    pub fn to_uleb128(&self) -> Vec<u8> {
+        let mut groups = radix::to_digits(self.magnitude(), 128);
+        groups.reverse();
+
+        with_continuation_bits(groups)
+    }
+
+    /// Returns the value in signed LEB128, in two's complement.
+    pub fn to_sleb128(&self) -> Vec<u8> {
+        // The sign bit must fit in the last group.
+        let bits = if self.is_negative() {
+            (self.abs() - 1u8).bit_length() + 1
+        } else {
+            self.bit_length() + 1
+        };
+
+        let count = bits.div_ceil(7);
+        let bytes = self.to_twos_complement((count * 7).div_ceil(8) + 1);
+
+        let groups = (0..count)
+            .map(|index| {
+                let position = index * 7;
+
+                let low = bytes[position / 8] as u16;
+                let high = bytes[position / 8 + 1] as u16;
+
+                ((low | high << 8) >> (position % 8)) as u8 & 0x7F
+            })
+            .collect();
+
+        with_continuation_bits(groups)
+    }
+
+    /// Returns the value as a zigzag varint: `2n` for `n >= 0` and `-2n - 1` for `n < 0`, in
+    /// unsigned LEB128.
+    pub fn to_zigzag_varint(&self) -> Vec<u8> {
+        if self.is_negative() {
+            ((self.abs() << 1) - 1u8).to_uleb128()
+        } else {
+            (self << 1).to_uleb128()
+        }
+    }
+
+    /// Decode an unsigned LEB128 value from the start of `data`. Returns the value and the
+    /// number of bytes read.
+    pub fn from_uleb128(data: &[u8]) -> Result<(Self, usize), TruncatedVarintError> {
+        let mut groups = read_groups(data)?;
+        let length = groups.len();
+        groups.reverse();
+
+        let value = BigInt::from_magnitude(radix::from_digits(&groups, 128), false);
+
+        Ok((value, length))
+    }
+
+    /// Decode a signed LEB128 value from the start of `data`. Returns the value and the number
+    /// of bytes read.
+    pub fn from_sleb128(data: &[u8]) -> Result<(Self, usize), TruncatedVarintError> {
+        let (value, length) = BigInt::from_uleb128(data)?;
+
+        // The high bit of the last group is the sign bit.
+        if data[length - 1] & 0x40 != 0 {
+            Ok((value - (BigInt::from(1u8) << (length * 7)), length))
+        } else {
+            Ok((value, length))
+        }
+    }
+
+    /// Decode a zigzag varint from the start of `data`. Returns the value and the number of
+    /// bytes read.
+    pub fn from_zigzag_varint(data: &[u8]) -> Result<(Self, usize), TruncatedVarintError> {
+        let (value, length) = BigInt::from_uleb128(data)?;
+
+        if value.magnitude().first().is_some_and(|&low| low & 1 != 0) {
+            Ok((-((value + 1u8) >> 1), length))
+        } else {
+            Ok((value >> 1, length))
+        }
+    }
+}
+
+/// Set the continuation bit of every group but the last one.
+fn with_continuation_bits(mut groups: Vec<u8>) -> Vec<u8> {
+    let last = groups.len() - 1;
+
+    for group in &mut groups[..last] {
+        *group |= 0x80;
+    }
+
+    groups
+}
+
+/// Returns the 7-bit groups of the varint at the start of `data`, least significant first.
+fn read_groups(data: &[u8]) -> Result<Vec<u8>, TruncatedVarintError> {
+    let length = data
+        .iter()
+        .position(|&byte| byte & 0x80 == 0)
+        .ok_or(TruncatedVarintError { length: data.len() })?
+        + 1;
+
+    Ok(data[..length].iter().map(|&byte| byte & 0x7F).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigInt, TruncatedVarintError};
+
+    mod unsigned {
+        use super::*;
+
+        #[test]
+        fn should_encode_seven_bits_per_byte() {
+            // When / Then
+            assert_eq!(BigInt::from(0u8).to_uleb128(), vec![0x00]);
+            assert_eq!(BigInt::from(127u8).to_uleb128(), vec![0x7F]);
+            assert_eq!(BigInt::from(624485u32).to_uleb128(), vec![0xE5, 0x8E, 0x26]);
+        }
+
+        #[test]
+        fn should_report_the_bytes_read() {
+            // Given
+            let data = [0xE5, 0x8E, 0x26, 0xFF];
+
+            // When
+            let result = BigInt::from_uleb128(&data);
+
+            // Then
+            assert_eq!(result, Ok((BigInt::from(624485u32), 3)));
+        }
+
+        #[test]
+        fn should_round_trip_beyond_64_bits() {
+            // Given
+            let value = BigInt::from(u128::MAX) << 100;
+
+            // When
+            let encoded = value.to_uleb128();
+
+            // Then
+            assert_eq!(encoded.len(), 33);
+            assert_eq!(BigInt::from_uleb128(&encoded), Ok((value, 33)));
+        }
+
+        #[test]
+        fn should_reject_a_truncated_varint() {
+            // When
+            let error = BigInt::from_uleb128(&[0x80, 0x80]).unwrap_err();
+
+            // Then
+            assert_eq!(error, TruncatedVarintError { length: 2 });
+            assert_eq!(
+                error.to_string(),
+                "varint truncated, no last byte in 2 bytes"
+            );
+            assert!(BigInt::from_uleb128(&[]).is_err());
+        }
+    }
+
+    mod signed {
+        use super::*;
+
+        #[test]
+        fn should_encode_in_twos_complement() {
+            // When / Then
+            assert_eq!(BigInt::from(0u8).to_sleb128(), vec![0x00]);
+            assert_eq!(BigInt::from(63u8).to_sleb128(), vec![0x3F]);
+            assert_eq!(BigInt::from(64u8).to_sleb128(), vec![0xC0, 0x00]);
+            assert_eq!(BigInt::from(-1i8).to_sleb128(), vec![0x7F]);
+            assert_eq!(BigInt::from(-64i8).to_sleb128(), vec![0x40]);
+            assert_eq!(BigInt::from(-65i8).to_sleb128(), vec![0xBF, 0x7F]);
+            assert_eq!(
+                BigInt::from(-123456i32).to_sleb128(),
+                vec![0xC0, 0xBB, 0x78]
+            );
+        }
+
+        #[test]
+        fn should_round_trip() {
+            for value in [
+                0i128,
+                1,
+                -1,
+                64,
+                -64,
+                -65,
+                i64::MIN as i128,
+                i128::MIN,
+                i128::MAX,
+            ] {
+                // Given
+                let value = BigInt::from(value);
+
+                // When
+                let encoded = value.to_sleb128();
+
+                // Then
+                assert_eq!(BigInt::from_sleb128(&encoded), Ok((value, encoded.len())));
+            }
+        }
+    }
+
+    mod zigzag {
+        use super::*;
+
+        #[test]
+        fn should_interleave_the_signs() {
+            // When / Then
+            assert_eq!(BigInt::from(0i8).to_zigzag_varint(), vec![0]);
+            assert_eq!(BigInt::from(-1i8).to_zigzag_varint(), vec![1]);
+            assert_eq!(BigInt::from(1i8).to_zigzag_varint(), vec![2]);
+            assert_eq!(BigInt::from(-64i8).to_zigzag_varint(), vec![127]);
+            assert_eq!(BigInt::from(64i8).to_zigzag_varint(), vec![0x80, 0x01]);
+        }
+
+        #[test]
+        fn should_round_trip() {
+            for value in [0i128, 1, -1, 300, -300, i128::MIN, i128::MAX] {
+                // Given
+                let value = BigInt::from(value);
+
+                // When
+                let encoded = value.to_zigzag_varint();
+
+                // Then
+                assert_eq!(
+                    BigInt::from_zigzag_varint(&encoded),
+                    Ok((value, encoded.len()))
+                );
+            }
+        }
+
+        #[test]
+        fn should_encode_negative_zero_as_zero() {
+            // Given
+            let value = BigInt::from_bytes(vec![], true);
+
+            // When / Then
+            assert_eq!(value.to_zigzag_varint(), vec![0]);
+            assert_eq!(value.to_sleb128(), vec![0]);
+            assert_eq!(
+                BigInt::from_zigzag_varint(&value.to_zigzag_varint()),
+                Ok((BigInt::from(0u8), 1))
+            );
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Import and export of `BigInt` magnitudes as arrays of words, in the manner of GMP's
+//! `mpz_import` and `mpz_export`.
+
+use crate::BigInt;
+
+/// Which end of a sequence comes first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// The most significant word or byte comes first (big-endian).
+    MostSignificantFirst,
+    /// The least significant word or byte comes first (little-endian).
+    LeastSignificantFirst,
+}
+
+impl Order {
+    /// Returns the byte order of the target platform.
+    pub fn native() -> Order {
+        if cfg!(target_endian = "big") {
+            Order::MostSignificantFirst
+        } else {
+            Order::LeastSignificantFirst
+        }
+    }
+}
+
+/// The error returned when a [`WordLayout`] cannot be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordLayoutError {
+    /// The word size is not 1, 2, 4, 8 or 16 bytes.
+    InvalidWordSize(usize),
+    /// The nails leave no bit of the word for the value.
+    InvalidNails(u32),
+    /// The data ends in the middle of a word; the value is the length of the data.
+    PartialWord(usize),
+}
+
+impl std::fmt::Display for WordLayoutError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            WordLayoutError::InvalidWordSize(size) => write!(
+                formatter,
+                "invalid word size {}, expected 1, 2, 4, 8 or 16 bytes",
+                size
+            ),
+            WordLayoutError::InvalidNails(nails) => {
+                write!(formatter, "{} nail bits leave no room for the value", nails)
+            }
+            WordLayoutError::PartialWord(length) => write!(
+                formatter,
+                "{} bytes of data do not make a whole number of words",
+                length
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WordLayoutError {}
+
+/// The layout of an array of words, for [`BigInt::import_words`] and
+/// [`BigInt::export_words`].
+///
+/// By default the words and their bytes are least significant first, with no nail bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordLayout {
+    /// Size of a word, in bytes.
+    word_size: usize,
+
+    /// Order of the words in the array.
+    word_order: Order,
+
+    /// Order of the bytes within each word.
+    byte_order: Order,
+
+    /// Number of high bits of each word that are not part of the value.
+    nails: u32,
+}
+
+impl WordLayout {
+    /// Create a layout with words of `word_size` bytes: 1, 2, 4, 8 or 16.
+    pub fn new(word_size: usize) -> Self {
+        WordLayout {
+            word_size,
+            word_order: Order::LeastSignificantFirst,
+            byte_order: Order::LeastSignificantFirst,
+            nails: 0,
+        }
+    }
+
+    /// Set the order of the words in the array.
+    pub fn word_order(mut self, order: Order) -> Self {
+        self.word_order = order;
+        self
+    }
+
+    /// Set the order of the bytes within each word. Use [`Order::native`] for the arrays of
+    /// primitive integers of the platform.
+    pub fn byte_order(mut self, order: Order) -> Self {
+        self.byte_order = order;
+        self
+    }
+
+    /// Set the number of high bits of each word that are not part of the value. They are
+    /// ignored on import and written as zeros on export.
+    pub fn nails(mut self, nails: u32) -> Self {
+        self.nails = nails;
+        self
+    }
+
+    /// Returns the number of value bits in a word, if the layout is valid.
+    fn value_bits(&self) -> Result<usize, WordLayoutError> {
+        if !matches!(self.word_size, 1 | 2 | 4 | 8 | 16) {
+            return Err(WordLayoutError::InvalidWordSize(self.word_size));
+        }
+
+        let bits = self.word_size * 8;
+
+        if self.nails as usize >= bits {
+            return Err(WordLayoutError::InvalidNails(self.nails));
+        }
+
+        Ok(bits - self.nails as usize)
+    }
+
+    /// Returns the range of bytes of the word of significance `index` in an array of `count`
+    /// words.
+    fn word_range(&self, index: usize, count: usize) -> std::ops::Range<usize> {
+        let position = match self.word_order {
+            Order::LeastSignificantFirst => index,
+            Order::MostSignificantFirst => count - 1 - index,
+        };
+
+        position * self.word_size..(position + 1) * self.word_size
+    }
+}
+
+impl BigInt {
+    /// Create a new non-negative `BigInt` from an array of words laid out as `layout` says.
+    pub fn import_words(data: &[u8], layout: WordLayout) -> Result<Self, WordLayoutError> {
+        let value_bits = layout.value_bits()?;
+
+        if !data.len().is_multiple_of(layout.word_size) {
+            return Err(WordLayoutError::PartialWord(data.len()));
+        }
+
+        let count = data.len() / layout.word_size;
+        let mut result = vec![0u8; (count * value_bits).div_ceil(8)];
+        let mut position = 0;
+
+        for index in 0..count {
+            let mut word = data[layout.word_range(index, count)].to_vec();
+
+            if layout.byte_order == Order::MostSignificantFirst {
+                word.reverse();
+            }
+
+            // Copy the value bits of the word, byte by byte, from the low end.
+            for (byte_index, &byte) in word.iter().enumerate() {
+                let bits = value_bits.saturating_sub(byte_index * 8).min(8);
+                let byte = (byte as u16 & ((1 << bits) - 1)) << (position % 8);
+
+                result[position / 8] |= byte as u8;
+
+                if byte > 0xFF {
+                    result[position / 8 + 1] |= (byte >> 8) as u8;
+                }
+
+                position += bits;
+            }
+        }
+
+        Ok(BigInt::from_magnitude(result, false))
+    }
+
+    /// Returns the magnitude as an array of words laid out as `layout` says, in as few words as
+    /// possible. Zero is an empty array. The sign is dropped.
+    pub fn export_words(&self, layout: WordLayout) -> Result<Vec<u8>, WordLayoutError> {
+        let value_bits = layout.value_bits()?;
+
+        let data = self.magnitude();
+        let count = self.bit_length().div_ceil(value_bits);
+        let mut result = vec![0u8; count * layout.word_size];
+
+        for index in 0..count {
+            let word = &mut result[layout.word_range(index, count)];
+
+            // Fill the word from its low end with the next value bits of the magnitude.
+            for byte_index in 0..layout.word_size {
+                let bits = value_bits.saturating_sub(byte_index * 8).min(8);
+                let position = index * value_bits + byte_index * 8;
+
+                let low = *data.get(position / 8).unwrap_or(&0) as u16;
+                let high = *data.get(position / 8 + 1).unwrap_or(&0) as u16;
+                let byte = ((low | high << 8) >> (position % 8)) & ((1 << bits) - 1);
+
+                let byte_index = match layout.byte_order {
+                    Order::LeastSignificantFirst => byte_index,
+                    Order::MostSignificantFirst => layout.word_size - 1 - byte_index,
+                };
+
+                word[byte_index] = byte as u8;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigInt, Order, WordLayout, WordLayoutError};
+
+    mod import {
+        use super::*;
+
+        #[test]
+        fn should_read_words_in_any_order() {
+            // Given: 0x11223344_55667788 as two 32-bit words.
+            let expected = BigInt::from(0x1122_3344_5566_7788u64);
+
+            let big_endian = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88];
+            let mixed = [0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55];
+
+            // When
+            let a = BigInt::import_words(
+                &big_endian,
+                WordLayout::new(4)
+                    .word_order(Order::MostSignificantFirst)
+                    .byte_order(Order::MostSignificantFirst),
+            );
+            let b = BigInt::import_words(
+                &mixed,
+                WordLayout::new(4).word_order(Order::MostSignificantFirst),
+            );
+
+            // Then
+            assert_eq!(a, Ok(expected.clone()));
+            assert_eq!(b, Ok(expected));
+        }
+
+        #[test]
+        fn should_ignore_the_nail_bits() {
+            // Given: two 16-bit words with 4 nail bits each, holding 0xABC and 0x123.
+            let data = [0xBC, 0xFA, 0x23, 0xF1];
+
+            // When
+            let result = BigInt::import_words(&data, WordLayout::new(2).nails(4));
+
+            // Then
+            assert_eq!(result, Ok(BigInt::from(0x0012_3ABCu32)));
+        }
+
+        #[test]
+        fn should_reject_invalid_layouts() {
+            // When / Then
+            assert_eq!(
+                BigInt::import_words(&[0; 6], WordLayout::new(3)),
+                Err(WordLayoutError::InvalidWordSize(3))
+            );
+            assert_eq!(
+                BigInt::import_words(&[0; 6], WordLayout::new(4)),
+                Err(WordLayoutError::PartialWord(6))
+            );
+            assert_eq!(
+                BigInt::from(1u8).export_words(WordLayout::new(1).nails(8)),
+                Err(WordLayoutError::InvalidNails(8))
+            );
+        }
+    }
+
+    mod export {
+        use super::*;
+
+        #[test]
+        fn should_write_as_few_words_as_possible() {
+            // Given
+            let value = -BigInt::from(0x0001_0203_0405u64);
+
+            // When
+            let result = value.export_words(
+                WordLayout::new(2)
+                    .word_order(Order::MostSignificantFirst)
+                    .byte_order(Order::MostSignificantFirst),
+            );
+
+            // Then
+            assert_eq!(result, Ok(vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05]));
+            assert_eq!(
+                BigInt::from(0u8).export_words(WordLayout::new(8)),
+                Ok(vec![])
+            );
+        }
+
+        #[test]
+        fn should_round_trip_with_nails() {
+            // Given
+            let value = (BigInt::from(u128::MAX) << 77) + 12345u16;
+
+            for layout in [
+                WordLayout::new(16).nails(5),
+                WordLayout::new(8)
+                    .nails(3)
+                    .byte_order(Order::MostSignificantFirst),
+                WordLayout::new(1)
+                    .nails(1)
+                    .word_order(Order::MostSignificantFirst),
+                WordLayout::new(4).byte_order(Order::native()),
+            ] {
+                // When
+                let words = value.export_words(layout).unwrap();
+
+                // Then
+                assert_eq!(BigInt::import_words(&words, layout), Ok(value.clone()));
+            }
+        }
+    }
+}
@@ -1,7 +1,5 @@
 #[cfg(test)]
 mod bigint {
-    use bigint;
-
     #[test]
     fn should_create_bigint_from_an_empty_string() {
         // When